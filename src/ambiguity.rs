@@ -0,0 +1,89 @@
+use std::collections::*;
+
+use crate::reg_lang::*;
+use crate::regex::*;
+use crate::glushkov::*;
+
+// Two distinct numbered strings projecting to the same word
+pub type NumberedPair = (Vec<NumTerm>, Vec<NumTerm>);
+
+// Pair of states of the position automaton reached by reading the same word,
+// and whether the two numberings differ so far
+type PairNode = (i32, i32, bool);
+
+impl Regex {
+    // The expression is ambiguous if two distinct strings of the numbered
+    // language are equal once the numbers are erased, that is if two distinct
+    // runs of the position automaton accept the same word. The search is done
+    // breadth-first to find a shortest ambiguous word.
+    pub fn ambiguous_word(&self) -> Option<NumberedPair> {
+        let m = glushkov(self);
+        let start: PairNode = (0, 0, false);
+        let mut parent: HashMap<PairNode, PairNode> = HashMap::new();
+        let mut visited: HashSet<PairNode> = HashSet::from([start]);
+        let mut worklist = VecDeque::from([start]);
+        while let Some(node) = worklist.pop_front() {
+            let (p, q, diverged) = node;
+            if diverged && m.lookup_state(p).is_final && m.lookup_state(q).is_final {
+                return Some(rebuild_pair(&m, &parent, node));
+            }
+            for t1 in &m.lookup_state(p).transitions {
+                for t2 in m.lookup_state(q).transitions.iter().filter(|t2| t2.label == t1.label) {
+                    let next = (t1.dest_id, t2.dest_id, diverged || t1.dest_id != t2.dest_id);
+                    if visited.insert(next) {
+                        parent.insert(next, node);
+                        worklist.push_back(next);
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+fn rebuild_pair(m: &GlushkovMachine, parent: &HashMap<PairNode, PairNode>, end: PairNode) -> NumberedPair {
+    let (mut left, mut right) = (vec![], vec![]);
+    let mut node = end;
+    while let (Some(p), Some(q)) = (m.lookup_state(node.0).label, m.lookup_state(node.1).label) {
+        left.push(p);
+        right.push(q);
+        node = parent[&node];
+    }
+    left.reverse();
+    right.reverse();
+    (left, right)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::*;
+    use super::*;
+
+    fn numbering(terms: &[NumTerm]) -> String {
+        terms.iter().map(|t| t.to_string()).collect::<Vec<_>>().join(" ")
+    }
+
+    #[test]
+    fn shortest_ambiguous_word() {
+        for (re, first, second) in [("a|a", "a1", "a2"), ("a*a*", "a1", "a2"), ("(a|ab)(c|bc)", "a1 b5 c6", "a2 b3 c4")] {
+            let (w1, w2) = regex(re).ambiguous_word().unwrap();
+            assert_eq!((numbering(&w1), numbering(&w2)), (first.to_string(), second.to_string()), "{re}");
+        }
+    }
+
+    #[test]
+    fn unambiguous_expressions() {
+        for re in ["(a|b)*", "(ab)*", "a*b*", "(a*b*)*", "(a|ab)(c|d)", "_"] {
+            assert_eq!(regex(re).ambiguous_word(), None, "{re}");
+        }
+    }
+
+    #[test]
+    fn numberings_project_to_the_same_word() {
+        for re in ["(a|ab)*(b|_)", "(a|b)*a(a|b)*a", "(a|b)*(ab|b)"] {
+            let (w1, w2) = regex(re).ambiguous_word().unwrap();
+            assert_ne!(w1, w2);
+            assert_eq!(w1.iter().map(|t| t.c).collect::<String>(), w2.iter().map(|t| t.c).collect::<String>());
+        }
+    }
+}
@@ -1,29 +1,12 @@
-use std::fmt;
+pub mod search;
 
 use crate::reg_lang::*;
 use crate::regex::*;
 use crate::fsm::*;
 
-pub struct BMCMachineLabel {
-    name: char,
-    gen_id: usize
-}
-
-impl DotFormat for BMCMachineLabel {
-    fn to_dot(&self, _: bool) -> String {
-        format!("<{}<sub>{}</sub>>", self.name, self.gen_id)
-    }
-}
-
-impl fmt::Display for BMCMachineLabel {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}{}", self.name, self.gen_id)
-    }
-}
-
 pub type BMCTransition = BaseTransition<Regex>;
 pub type BMCState = BaseState<StateLabel, Regex>;
-pub type BMCMachine = BaseMachine<BMCMachineLabel, StateLabel, Regex>;
+pub type BMCMachine = BaseMachine<GenMachineLabel, StateLabel, Regex>;
 
 impl BMCMachine {
     pub fn from_machine(old_m: &Machine) -> BMCMachine {
@@ -75,7 +58,7 @@ impl BMCMachine {
         }).collect();
         states.push(ini);
         states.push(fin);
-        BMCMachine{ label: BMCMachineLabel{ name: old_m.label, gen_id: 1 }, states }
+        BMCMachine{ label: GenMachineLabel{ name: old_m.label, gen_id: 1 }, states }
     }
 
     pub fn merge_parallel_transitions(&mut self) {
@@ -87,7 +70,7 @@ impl BMCMachine {
                     if s.transitions[i].dest_id == s.transitions[j].dest_id {
                         let re1 = Box::new(s.transitions[i].label.clone());
                         let re2 = Box::new(s.transitions[j].label.clone());
-                        s.transitions[i].label = Regex::Union(re1, re2);
+                        s.transitions[i].label = Regex::Union(re1, re2).simplify();
                         s.transitions.remove(j);
                     } else {
                         j += 1;
@@ -123,7 +106,7 @@ impl BMCMachine {
             };
             let mut new_ts: Vec<BMCTransition> = Vec::new();
             for t2 in &t_out {
-                let re = Regex::Concat(Box::new(lhs.clone()), Box::new(t2.label.clone()));
+                let re = Regex::Concat(Box::new(lhs.clone()), Box::new(t2.label.clone())).simplify();
                 new_ts.push(BMCTransition{
                     dest_id: t2.dest_id,
                     label: re
@@ -0,0 +1,107 @@
+use std::collections::*;
+
+use crate::bmc::*;
+use crate::regex::metrics::*;
+
+const BEAM_WIDTH: usize = 32;
+
+// A partial elimination order, with its cost and the resulting machine
+type Candidate = ((usize, usize), Vec<i32>, BMCMachine);
+
+// Size of a partially reduced machine: the metric is summed over all labels,
+// except for the star height where the maximum is taken. Ties are broken by
+// the total length.
+fn partial_cost(m: &BMCMachine, metric: RegexMetric) -> (usize, usize) {
+    m.states.iter().flat_map(|s| s.transitions.iter()).fold((0, 0), |(acc, len), t| {
+        let v = metric.measure(&t.label);
+        let acc = if metric == RegexMetric::StarHeight { acc.max(v) } else { acc + v };
+        (acc, len + RegexMetric::Length.measure(&t.label))
+    })
+}
+
+// Beam search over the elimination orders. Orders eliminating the same set of
+// states are merged by keeping the one leading to the smallest machine.
+pub fn bmc_find_best(fsm: &Machine, metric: RegexMetric) -> (Vec<i32>, usize) {
+    let mut start = BMCMachine::from_machine(fsm);
+    start.merge_parallel_transitions();
+    let mut beam: Vec<Candidate> = vec![(partial_cost(&start, metric), vec![], start)];
+    for _ in 0..fsm.states.len() {
+        let mut next: HashMap<BTreeSet<i32>, Candidate> = HashMap::new();
+        for (_, order, m) in &beam {
+            for s in m.states.iter().filter(|s| !(s.is_initial || s.is_final)) {
+                let mut new_m = m.clone();
+                new_m.eliminate(s.id);
+                new_m.merge_parallel_transitions();
+                let cost = partial_cost(&new_m, metric);
+                let mut new_order = order.clone();
+                new_order.push(s.id);
+                let key: BTreeSet<i32> = new_order.iter().cloned().collect();
+                if next.get(&key).is_none_or(|(best, _, _)| cost < *best) {
+                    next.insert(key, (cost, new_order, new_m));
+                }
+            }
+        }
+        let mut candidates: Vec<Candidate> = next.into_values().collect();
+        candidates.sort_by(|(c1, o1, _), (c2, o2, _)| (c1, o1).cmp(&(c2, o2)));
+        candidates.truncate(BEAM_WIDTH);
+        beam = candidates;
+    }
+    let ((cost, _), order, _) = beam.swap_remove(0);
+    (order, cost)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::*;
+    use super::*;
+
+    const FILES: [&str; 4] = [
+        "tests/fsm_bmc_2025-01-17.txt",
+        "tests/fsm_bmc_2025-02-12.txt",
+        "tests/fsm_bmc_2025-02-12_alt.txt",
+        "tests/fsm_bmc_2025-07-09.txt"
+    ];
+
+    fn eliminate_all(fsm: &Machine, order: &[i32]) -> Regex {
+        let mut m = BMCMachine::from_machine(fsm);
+        m.merge_parallel_transitions();
+        for sid in order {
+            m.eliminate(*sid);
+            m.merge_parallel_transitions();
+        }
+        m.regex()
+    }
+
+    #[test]
+    fn best_order_gives_equivalent_regex() {
+        for file in FILES {
+            let fsm = load_machine(file);
+            for metric in [RegexMetric::Length, RegexMetric::Literals, RegexMetric::Size, RegexMetric::StarHeight] {
+                let (order, cost) = bmc_find_best(&fsm, metric);
+                let mut sorted = order.clone();
+                sorted.sort();
+                assert_eq!(sorted, fsm.states_ids());
+                let re = eliminate_all(&fsm, &order);
+                assert_eq!(metric.measure(&re), cost);
+                let m = regex_machine(&re.to_string());
+                assert_eq!(m.words(6, 50), fsm.words(6, 50));
+                assert_equivalent(&m, &fsm);
+            }
+        }
+    }
+
+    #[test]
+    fn best_order_beats_heuristic() {
+        for file in FILES {
+            let fsm = load_machine(file);
+            let mut m = BMCMachine::from_machine(&fsm);
+            m.merge_parallel_transitions();
+            while let Some(sid) = m.choose_best_state() {
+                m.eliminate(sid);
+                m.merge_parallel_transitions();
+            }
+            let (_, cost) = bmc_find_best(&fsm, RegexMetric::Length);
+            assert!(cost <= RegexMetric::Length.measure(&m.regex()), "{file}");
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use std::collections::*;
+
+use crate::reg_lang::*;
+use crate::regex::*;
+use crate::fsm::*;
+
+pub type DerivState = BaseState<Regex, char>;
+pub type DerivMachine = BaseMachine<char, Regex, char>;
+
+// The empty language is represented by None
+fn concat_opt(r1: Option<Regex>, r2: &Regex) -> Option<Regex> {
+    r1.map(|r1| Regex::Concat(Box::new(r1), Box::new(r2.clone())))
+}
+
+impl Regex {
+    pub fn derive(&self, c: char) -> Option<Regex> {
+        match self {
+            Regex::Null => None,
+            Regex::Literal(t) => if t.c == c { Some(Regex::Null) } else { None },
+            Regex::Union(r1, r2) => union_opt(r1.derive(c), r2.derive(c)),
+            Regex::Concat(r1, r2) => {
+                let lhs = concat_opt(r1.derive(c), r2);
+                if r1.nullable() {
+                    union_opt(lhs, r2.derive(c))
+                } else {
+                    lhs
+                }
+            }
+            Regex::Star(r1) => concat_opt(r1.derive(c), self),
+            Regex::Plus(r1) => concat_opt(r1.derive(c), &Regex::Star(r1.clone())),
+            Regex::Complement(_)
+            | Regex::Intersection(_, _) => panic!("derivative of an extended regular expression")
+        }
+    }
+}
+
+impl DerivState {
+    fn new(label: Regex, is_initial: bool) -> DerivState {
+        let is_final = label.nullable();
+        DerivState{ id: -1, label, transitions: Vec::new(), is_initial, is_final }
+    }
+}
+
+impl DerivMachine {
+    fn try_lookup_state_by_label(&self, label: &Regex) -> Option<&DerivState> {
+        let key = label.to_string();
+        self.states.iter().find(|s| s.label.to_string() == key)
+    }
+
+    fn insert(&mut self, mut new: DerivState) -> i32 {
+        if let Some(s) = self.try_lookup_state_by_label(&new.label) {
+            return s.id;
+        }
+        let id = self.states.len() as i32;
+        new.id = id;
+        self.states.push(new);
+        id
+    }
+}
+
+pub fn derivatives_dfa(re: &Regex) -> DerivMachine {
+    let mut alphabet: Vec<char> = re.all_numbered().into_iter().map(|t| t.c).collect();
+    alphabet.sort();
+    alphabet.dedup();
+
+    let mut res = DerivMachine::new('d');
+    let mut worklist = VecDeque::from([res.insert(DerivState::new(re.normalized(), true))]);
+    let mut visited: HashSet<i32> = HashSet::new();
+    while let Some(state_id) = worklist.pop_front() {
+        if visited.contains(&state_id) {
+            continue;
+        }
+        visited.insert(state_id);
+
+        let state = res.lookup_state(state_id);
+        let shifts: Vec<_> = alphabet.iter().filter_map(|c| {
+            state.label.derive(*c).map(|d| (*c, d.normalized()))
+        }).collect();
+        let xions: Vec<_> = shifts.into_iter().map(|(c, label)| {
+            let id = res.insert(DerivState::new(label, false));
+            Transition{ label: c, dest_id: id }
+        }).collect();
+        worklist.extend(xions.iter().map(|xion| xion.dest_id));
+        res.lookup_state_mut(state_id).transitions = xions;
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::berry_sethi::*;
+    use crate::test_util::*;
+    use super::*;
+
+    #[test]
+    fn derivative_of_concatenation() {
+        let re = regex("a*b");
+        assert_eq!(re.derive('b').map(|d| d.normalized()).map(|d| d.to_string()), Some("_".to_string()));
+        assert!(re.derive('c').is_none());
+        assert!(re.derive('a').unwrap().derive('b').unwrap().nullable());
+    }
+
+    #[test]
+    fn dfa_is_deterministic_and_equivalent() {
+        for re in ["(a|b)*abb", "a+b*|c", "(ab|_)(c|d)+", "((a|b)*c)*", "_"].map(regex) {
+            let dfa = Machine::from_machine(derivatives_dfa(&re));
+            assert!(dfa.is_deterministic());
+            assert_equivalent(&dfa, &Machine::from_machine(berry_sethi(&re)));
+        }
+    }
+}
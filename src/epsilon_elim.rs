@@ -1,5 +1,38 @@
+use std::fmt;
+
 use crate::fsm::*;
 
+// A change made to a machine while eliminating its spontaneous moves, with
+// the states that justify it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EpsilonElimStep {
+    Transitivity(i32, i32, i32),
+    BackwardPropagation(i32, i32, char, i32),
+    ForwardPropagation(i32, char, i32, i32),
+    NewFinal(i32, i32),
+    NewInitial(i32, i32),
+    Removal(i32, i32)
+}
+
+impl fmt::Display for EpsilonElimStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            EpsilonElimStep::Transitivity(src_id, mid_id, dest_id) =>
+                write!(f, "Adding {src_id} -ε-> {dest_id} by transitivity of {src_id} -ε-> {mid_id} -ε-> {dest_id}"),
+            EpsilonElimStep::BackwardPropagation(src_id, mid_id, c, dest_id) =>
+                write!(f, "Adding {src_id} -{c}-> {dest_id} by backward propagation of {src_id} -ε-> {mid_id} -{c}-> {dest_id}"),
+            EpsilonElimStep::ForwardPropagation(src_id, c, mid_id, dest_id) =>
+                write!(f, "Adding {src_id} -{c}-> {dest_id} by forward propagation of {src_id} -{c}-> {mid_id} -ε-> {dest_id}"),
+            EpsilonElimStep::NewFinal(id, final_id) =>
+                write!(f, "State {id} is now final because {id} -ε-> {final_id} and {final_id} is final"),
+            EpsilonElimStep::NewInitial(initial_id, id) =>
+                write!(f, "State {id} is now initial because {initial_id} -ε-> {id} and {initial_id} is initial"),
+            EpsilonElimStep::Removal(src_id, dest_id) =>
+                write!(f, "Removing {src_id} -ε-> {dest_id}")
+        }
+    }
+}
+
 impl Machine {
     fn add_epsilon_transition(&mut self, src_id: i32, dest_id: i32) -> bool {
         let src = self.lookup_state_mut(src_id);
@@ -11,114 +44,197 @@ impl Machine {
         }
     }
 
-    pub fn epsilon_trans_closure(&mut self) {
+    pub fn epsilon_trans_closure(&mut self) -> Vec<EpsilonElimStep> {
+        let mut steps: Vec<EpsilonElimStep> = vec![];
         loop {
-            let mut to_add: Vec<(i32, i32)> = vec![];
+            let mut to_add: Vec<(i32, i32, i32)> = vec![];
             for s in &self.states {
                 for t in &s.transitions {
                     if t.is_epsilon() {
                         let dest_s = self.lookup_state(t.dest_id);
                         for next_t in &dest_s.transitions {
                             if next_t.is_epsilon() {
-                                to_add.push((s.id, next_t.dest_id));
+                                to_add.push((s.id, t.dest_id, next_t.dest_id));
                             }
                         }
                     }
                 }
             }
             let mut changed = false;
-            for (src_id, dest_id) in to_add {
-                changed |= self.add_epsilon_transition(src_id, dest_id);
+            for (src_id, mid_id, dest_id) in to_add {
+                if self.add_epsilon_transition(src_id, dest_id) {
+                    steps.push(EpsilonElimStep::Transitivity(src_id, mid_id, dest_id));
+                    changed = true;
+                }
             }
             if !changed {
                 break;
             }
         }
+        steps
     }
 
-    fn add_transition(&mut self, src_id: i32, c: char, dest_id: i32) {
+    fn add_transition(&mut self, src_id: i32, c: char, dest_id: i32) -> bool {
         let src = self.lookup_state_mut(src_id);
-        if !src.transitions.iter().any(|t| t.dest_id == dest_id && t.label == c) {
+        if src.transitions.iter().any(|t| t.dest_id == dest_id && t.label == c) {
+            false
+        } else {
             src.transitions.push(Transition{ dest_id, label: c });
+            true
         }
     }
 
-    pub fn mark_new_final(&mut self) {
-        let mut to_add: Vec<i32> = vec![];
+    pub fn mark_new_final(&mut self) -> Vec<EpsilonElimStep> {
+        let mut steps: Vec<EpsilonElimStep> = vec![];
+        let mut to_add: Vec<(i32, i32)> = vec![];
         for s in &self.states {
             for t in &s.transitions {
                 if t.is_epsilon() && self.lookup_state(t.dest_id).is_final {
-                    to_add.push(s.id);
+                    to_add.push((s.id, t.dest_id));
                 }
             }
         }
-        for id in to_add {
-            self.lookup_state_mut(id).is_final = true;
+        for (id, final_id) in to_add {
+            let s = self.lookup_state_mut(id);
+            if !s.is_final {
+                steps.push(EpsilonElimStep::NewFinal(id, final_id));
+                s.is_final = true;
+            }
         }
+        steps
     }
 
-    pub fn backward_propagation(&mut self) {
-        let mut to_add: Vec<(i32, char, i32)> = vec![];
+    pub fn backward_propagation(&mut self) -> Vec<EpsilonElimStep> {
+        let mut steps: Vec<EpsilonElimStep> = vec![];
+        let mut to_add: Vec<(i32, i32, char, i32)> = vec![];
         for s in &self.states {
             for t in &s.transitions {
                 if t.is_epsilon() {
                     let dest_s = self.lookup_state(t.dest_id);
                     for next_t in &dest_s.transitions {
                         if !next_t.is_epsilon() {
-                            to_add.push((s.id, next_t.label, next_t.dest_id));
+                            to_add.push((s.id, t.dest_id, next_t.label, next_t.dest_id));
                         }
                     }
                 }
             }
         }
-        for (src_id, c, dest_id) in to_add {
-            self.add_transition(src_id, c, dest_id);
+        for (src_id, mid_id, c, dest_id) in to_add {
+            if self.add_transition(src_id, c, dest_id) {
+                steps.push(EpsilonElimStep::BackwardPropagation(src_id, mid_id, c, dest_id));
+            }
         }
-        self.mark_new_final();
+        steps.extend(self.mark_new_final());
+        steps
     }
 
-    pub fn mark_new_initial(&mut self) {
-        let mut to_add: Vec<i32> = vec![];
+    pub fn mark_new_initial(&mut self) -> Vec<EpsilonElimStep> {
+        let mut steps: Vec<EpsilonElimStep> = vec![];
+        let mut to_add: Vec<(i32, i32)> = vec![];
         for s in &self.states {
             if s.is_initial {
                 for t in &s.transitions {
                     if t.is_epsilon() {
-                        to_add.push(t.dest_id);
+                        to_add.push((s.id, t.dest_id));
                     }
                 }
             }
         }
-        for id in to_add {
-            self.lookup_state_mut(id).is_initial = true;
+        for (initial_id, id) in to_add {
+            let s = self.lookup_state_mut(id);
+            if !s.is_initial {
+                steps.push(EpsilonElimStep::NewInitial(initial_id, id));
+                s.is_initial = true;
+            }
         }
+        steps
     }
 
-    pub fn forward_propagation(&mut self) {
-        let mut to_add: Vec<(i32, char, i32)> = vec![];
+    pub fn forward_propagation(&mut self) -> Vec<EpsilonElimStep> {
+        let mut steps: Vec<EpsilonElimStep> = vec![];
+        let mut to_add: Vec<(i32, char, i32, i32)> = vec![];
         for s in &self.states {
             for t in &s.transitions {
                 if !t.is_epsilon() {
                     let dest_s = self.lookup_state(t.dest_id);
                     for next_t in &dest_s.transitions {
                         if next_t.is_epsilon() {
-                            to_add.push((s.id, t.label, next_t.dest_id));
+                            to_add.push((s.id, t.label, t.dest_id, next_t.dest_id));
                         }
                     }
                 }
             }
         }
-        for (src_id, c, dest_id) in to_add {
-            self.add_transition(src_id, c, dest_id);
+        for (src_id, c, mid_id, dest_id) in to_add {
+            if self.add_transition(src_id, c, dest_id) {
+                steps.push(EpsilonElimStep::ForwardPropagation(src_id, c, mid_id, dest_id));
+            }
         }
-        self.mark_new_initial();
+        steps.extend(self.mark_new_initial());
+        steps
     }
 
-    pub fn remove_epsilon_trans(&mut self) {
+    pub fn remove_epsilon_trans(&mut self) -> Vec<EpsilonElimStep> {
+        let mut steps: Vec<EpsilonElimStep> = vec![];
         for s in &mut self.states {
+            for t in &s.transitions {
+                if t.is_epsilon() {
+                    steps.push(EpsilonElimStep::Removal(s.id, t.dest_id));
+                }
+            }
             let new_ts: Vec<_> = s.transitions.iter().filter(|t| {
                 !t.is_epsilon()
             }).cloned().collect();
             s.transitions = new_ts;
         }
+        steps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::*;
+    use super::*;
+
+    const FILES: [&str; 4] = [
+        "tests/fsm_backprop_2015-02-09.txt",
+        "tests/fsm_backprop_2023-06-08.txt",
+        "tests/fsm_backprop_2025-02-12.txt",
+        "tests/fsm_backprop_book-3.12.txt"
+    ];
+
+    fn eliminated(file: &str, forward: bool) -> Machine {
+        let mut fsm = load_machine(file);
+        fsm.epsilon_trans_closure();
+        if forward {
+            fsm.forward_propagation();
+        } else {
+            fsm.backward_propagation();
+        }
+        fsm.remove_epsilon_trans();
+        fsm
+    }
+
+    #[test]
+    fn elimination_preserves_language() {
+        for file in FILES {
+            for forward in [false, true] {
+                let fsm = eliminated(file, forward);
+                assert!(fsm.states.iter().all(|s| s.transitions.iter().all(|t| !t.is_epsilon())));
+                assert_equivalent(&fsm, &load_machine(file));
+            }
+        }
+    }
+
+    #[test]
+    fn steps_are_reported() {
+        let mut fsm = load_machine(FILES[0]);
+        assert_eq!(fsm.epsilon_trans_closure(), vec![EpsilonElimStep::Transitivity(3, 0, 1)]);
+        let steps = fsm.backward_propagation();
+        assert_eq!(steps[0], EpsilonElimStep::BackwardPropagation(0, 1, 'a', 2));
+        assert!(steps.contains(&EpsilonElimStep::NewFinal(0, 1)));
+        assert_eq!(steps[0].to_string(), "Adding 0 -a-> 2 by backward propagation of 0 -ε-> 1 -a-> 2");
+        assert_eq!(fsm.remove_epsilon_trans().len(), 3);
+        assert!(fsm.epsilon_trans_closure().is_empty());
     }
 }
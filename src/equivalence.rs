@@ -0,0 +1,31 @@
+use crate::fsm::*;
+use crate::product::*;
+
+// Returns the shortest word accepted by exactly one of the machines, and
+// whether it is accepted by the first one
+pub fn distinguishing_word(m1: &Machine, m2: &Machine) -> Option<(String, bool)> {
+    let word = m1.product(m2, BoolOp::SymmetricDifference).shortest_word()?;
+    let accepted_by_first = m1.accepts(&word);
+    Some((word, accepted_by_first))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::*;
+    use super::*;
+
+    #[test]
+    fn equivalent_machines() {
+        assert_eq!(distinguishing_word(&regex_machine("(a|b)*"), &regex_machine("(a*b*)*")), None);
+        assert_eq!(distinguishing_word(&regex_machine("a(ba)*"), &regex_machine("(ab)*a")), None);
+    }
+
+    #[test]
+    fn shortest_witness() {
+        let (m1, m2) = (regex_machine("a*"), regex_machine("a+"));
+        assert_eq!(distinguishing_word(&m1, &m2), Some(("".to_string(), true)));
+        assert_eq!(distinguishing_word(&m2, &m1), Some(("".to_string(), false)));
+        let (m1, m2) = (regex_machine("(a|b)*"), regex_machine("a*b*"));
+        assert_eq!(distinguishing_word(&m1, &m2), Some(("ba".to_string(), true)));
+    }
+}
@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+
+use crate::fsm::*;
+use crate::reg_lang::*;
+use crate::regex::*;
+use crate::berry_sethi::*;
+use crate::product::*;
+
+impl Regex {
+    // Whether the expression uses complement or intersection
+    pub fn is_extended(&self) -> bool {
+        match self {
+            Regex::Null | Regex::Literal(_) => false,
+            Regex::Union(r1, r2)
+            | Regex::Concat(r1, r2) => r1.is_extended() || r2.is_extended(),
+            Regex::Star(r1)
+            | Regex::Plus(r1) => r1.is_extended(),
+            Regex::Complement(_)
+            | Regex::Intersection(_, _) => true
+        }
+    }
+
+    pub fn alphabet(&self) -> Vec<char> {
+        let mut res: Vec<char> = self.all_numbered().iter().map(|t| t.c).collect();
+        res.sort();
+        res.dedup();
+        res
+    }
+}
+
+// Start of the private use area, whose characters never appear in expressions
+const FRESH_LETTERS: u32 = 0xE000;
+
+// Replaces the outermost complements and intersections by fresh letters,
+// numbered after the existing positions, and records their machines
+fn abstract_extended(re: &Regex, alphabet: &[char], images: &mut HashMap<char, Machine>, next_i: &mut usize) -> Regex {
+    match re {
+        Regex::Complement(_) | Regex::Intersection(_, _) => {
+            let c = char::from_u32(FRESH_LETTERS + images.len() as u32).unwrap();
+            images.insert(c, regex_to_machine_over(re, alphabet));
+            *next_i += 1;
+            Regex::Literal(NumTerm{ c, i: *next_i })
+        }
+        Regex::Null | Regex::Literal(_) => re.clone(),
+        Regex::Union(r1, r2) => Regex::Union(
+            Box::new(abstract_extended(r1, alphabet, images, next_i)),
+            Box::new(abstract_extended(r2, alphabet, images, next_i))),
+        Regex::Concat(r1, r2) => Regex::Concat(
+            Box::new(abstract_extended(r1, alphabet, images, next_i)),
+            Box::new(abstract_extended(r2, alphabet, images, next_i))),
+        Regex::Star(r1) => Regex::Star(Box::new(abstract_extended(r1, alphabet, images, next_i))),
+        Regex::Plus(r1) => Regex::Plus(Box::new(abstract_extended(r1, alphabet, images, next_i)))
+    }
+}
+
+// Complements (with respect to the given alphabet) and intersections are built
+// with the complement and product constructions on the Berry-Sethi machines of
+// their operands. Elsewhere in the expression, they stand for fresh letters that
+// are substituted once the Berry-Sethi machine of the whole expression is built.
+pub fn regex_to_machine_over(re: &Regex, alphabet: &[char]) -> Machine {
+    match re {
+        Regex::Complement(r1) => regex_to_machine_over(r1, alphabet).complement(alphabet),
+        Regex::Intersection(r1, r2) => {
+            let m1 = regex_to_machine_over(r1, alphabet);
+            let m2 = regex_to_machine_over(r2, alphabet);
+            Machine::from_machine(m1.product(&m2, BoolOp::Intersection))
+        }
+        _ if !re.is_extended() => Machine::from_machine(berry_sethi(re)),
+        _ => {
+            let mut images: HashMap<char, Machine> = HashMap::new();
+            let mut next_i = re.all_numbered().iter().map(|t| t.i).max().unwrap_or(0);
+            let plain = abstract_extended(re, alphabet, &mut images, &mut next_i);
+            Machine::from_machine(berry_sethi(&plain)).substitute(&images)
+        }
+    }
+}
+
+// Alphabet over which the complements of two compared languages are taken, so
+// that both sides agree on the universe
+pub fn merge_alphabets(a1: &[char], a2: &[char]) -> Vec<char> {
+    let mut res: Vec<char> = a1.iter().chain(a2).copied().collect();
+    res.sort();
+    res.dedup();
+    res
+}
+
+pub fn regex_to_machine(re: &Regex) -> Machine {
+    regex_to_machine_over(re, &re.alphabet())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::*;
+
+    #[test]
+    fn extended_operators() {
+        assert!(regex("~a").is_extended() && regex("a&b*").is_extended());
+        assert!(!regex("a-c{2}").is_extended());
+        assert_eq!(regex("(a|c)*&~b").alphabet(), vec!['a', 'b', 'c']);
+    }
+
+    #[test]
+    fn extended_semantics() {
+        assert_equivalent(&regex_machine("(a|b)*&~(a*)"), &regex_machine("(a|b)*b(a|b)*"));
+        assert_equivalent(&regex_machine("(a|b)*a&(a|b)*b(a|b)*"), &regex_machine("(a|b)*b(a|b)*a"));
+        // Complements are taken over the letters of the whole expression
+        assert_eq!(regex_machine("~(a*)&b*").words(2, 10), vec!["b", "bb"]);
+    }
+
+    #[test]
+    fn nested_extended_subexpressions() {
+        // The intersection stands for a letter of the enclosing Berry-Sethi machine
+        assert_equivalent(&regex_machine("a(~(b*)&(b|c)*)+"), &regex_machine("a(b|c)*c(b|c)*"));
+        assert_equivalent(&regex_machine("(~a&a*)b|c"), &regex_machine("(_|aaa*)b|c"));
+    }
+
+    #[test]
+    fn shared_alphabet() {
+        let (re_1, re_2) = (regex("~a"), regex("~a|b"));
+        let alphabet = merge_alphabets(&re_1.alphabet(), &re_2.alphabet());
+        assert_eq!(alphabet, vec!['a', 'b']);
+        assert_equivalent(&regex_to_machine_over(&re_1, &alphabet), &regex_to_machine_over(&re_2, &alphabet));
+    }
+}
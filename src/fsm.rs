@@ -3,6 +3,7 @@ mod validation;
 
 use std::collections::VecDeque;
 use std::collections::HashSet;
+use std::collections::HashMap;
 
 use crate::reg_lang::*;
 
@@ -34,7 +35,7 @@ pub struct StateLabel {
     pub m_name: char
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct BaseState<SL, TL> {
     pub id: i32,
     pub label: SL,
@@ -45,7 +46,14 @@ pub struct BaseState<SL, TL> {
 
 pub type State = BaseState<StateLabel, char>;
 
-#[derive(Debug)]
+// Labels a machine in a sequence of transformation steps
+#[derive(Debug, Clone, Copy)]
+pub struct GenMachineLabel {
+    pub name: char,
+    pub gen_id: usize
+}
+
+#[derive(Debug, Clone)]
 pub struct BaseMachine<ML, SL, TL> {
     pub label: ML,
     pub states: Vec<BaseState<SL, TL>>
@@ -95,6 +103,105 @@ impl<ML, SL, TL> BaseMachine<ML, SL, TL> {
             })
         }).collect()
     }
+
+    pub fn reachable_states_ids(&self) -> HashSet<i32> {
+        let mut worklist: VecDeque<_> = self.initial_states_ids().into();
+        let mut res: HashSet<i32> = HashSet::new();
+        while let Some(sid) = worklist.pop_front() {
+            if !res.insert(sid) {
+                continue;
+            }
+            if let Some(s) = self.try_lookup_state(sid) {
+                worklist.extend(s.transitions.iter().map(|t| t.dest_id));
+            }
+        }
+        res
+    }
+
+    pub fn coreachable_states_ids(&self) -> HashSet<i32> {
+        let mut worklist: VecDeque<_> = self.final_states_ids().into();
+        let mut res: HashSet<i32> = HashSet::new();
+        while let Some(sid) = worklist.pop_front() {
+            if !res.insert(sid) {
+                continue;
+            }
+            worklist.extend(self.transitions_to(sid).iter().map(|(src_id, _)| *src_id));
+        }
+        res
+    }
+
+    pub fn remove_states(&mut self, ids: &[i32]) {
+        self.states.retain(|s| !ids.contains(&s.id));
+        for s in &mut self.states {
+            s.transitions.retain(|t| !ids.contains(&t.dest_id));
+        }
+    }
+
+    pub fn trim(&mut self) -> Vec<i32> {
+        let reachable = self.reachable_states_ids();
+        let coreachable = self.coreachable_states_ids();
+        let useless: Vec<i32> = self.states.iter().filter_map(|s| {
+            if reachable.contains(&s.id) && coreachable.contains(&s.id) { None } else { Some(s.id) }
+        }).collect();
+        self.remove_states(&useless);
+        useless
+    }
+}
+
+impl<ML, SL: Clone, TL: Clone> BaseMachine<ML, SL, TL> {
+    pub fn with_label<ML2>(&self, label: ML2) -> BaseMachine<ML2, SL, TL> {
+        BaseMachine{ label, states: self.states.clone() }
+    }
+}
+
+impl<ML, SL> BaseMachine<ML, SL, char> {
+    pub fn next_state(&self, id: i32, c: char) -> Option<i32> {
+        self.lookup_state(id).transitions.iter().find(|t| t.label == c).map(|t| t.dest_id)
+    }
+
+    pub fn alphabet(&self) -> Vec<char> {
+        let mut res: Vec<char> = self.states.iter().flat_map(|s| {
+            s.transitions.iter().filter_map(|t| if t.label != '_' { Some(t.label) } else { None })
+        }).collect();
+        res.sort();
+        res.dedup();
+        res
+    }
+
+    pub fn shortest_word(&self) -> Option<String> {
+        let mut parent: HashMap<i32, (i32, char)> = HashMap::new();
+        let mut visited: HashSet<i32> = self.initial_states_ids().into_iter().collect();
+        let mut worklist: VecDeque<i32> = self.initial_states_ids().into_iter().collect();
+        while let Some(sid) = worklist.pop_front() {
+            let s = self.lookup_state(sid);
+            if s.is_final {
+                let mut word: Vec<char> = vec![];
+                let mut cur = sid;
+                while let Some((prev, c)) = parent.get(&cur) {
+                    word.push(*c);
+                    cur = *prev;
+                }
+                return Some(word.into_iter().rev().collect());
+            }
+            let mut ts: Vec<_> = s.transitions.iter().collect();
+            ts.sort_by_key(|t| t.label);
+            for t in ts {
+                if visited.insert(t.dest_id) {
+                    parent.insert(t.dest_id, (sid, t.label));
+                    worklist.push_back(t.dest_id);
+                }
+            }
+        }
+        None
+    }
+
+    pub fn is_deterministic(&self) -> bool {
+        self.initial_states_ids().len() == 1 && self.states.iter().all(|s| {
+            s.transitions.iter().enumerate().all(|(i, t)| {
+                t.label != '_' && !s.transitions[i+1..].iter().any(|t2| t2.label == t.label)
+            })
+        })
+    }
 }
 
 pub type NumTransition = BaseTransition<NumTerm>;
@@ -226,6 +333,27 @@ impl NumLocalSets for NumMachine {
 }
 
 impl Machine {
+    // The machine with a single, non-final, initial state
+    pub fn empty_language(label: char) -> Machine {
+        let initial = State{ id: 0, label: StateLabel{ id: 0, m_name: label }, transitions: vec![], is_initial: true, is_final: false };
+        Machine{ label, states: vec![initial] }
+    }
+
+    pub fn renumber(&mut self) -> Vec<(i32, i32)> {
+        let mut ids = self.states_ids();
+        ids.sort();
+        let old_to_new: HashMap<i32, i32> = ids.iter().enumerate().map(|(i, id)| (*id, i as i32)).collect();
+        for s in &mut self.states {
+            s.id = old_to_new[&s.id];
+            s.label.id = s.id;
+            for t in &mut s.transitions {
+                t.dest_id = old_to_new[&t.dest_id];
+            }
+        }
+        self.states.sort_by_key(|s| s.id);
+        ids.into_iter().map(|id| (id, old_to_new[&id])).filter(|(old, new)| old != new).collect()
+    }
+
     pub fn from_machine<SL>(old_m: BaseMachine<char, SL, char>) -> Machine {
         let states = old_m.states.iter().map(|old_state| {
             let mut new_ts: Vec<Transition> = Vec::new();
@@ -234,9 +362,7 @@ impl Machine {
                 let label = old_t.label;
                 new_ts.push(Transition{ label, dest_id });
             }
-            let sl = StateLabel{
-                id: old_state.id,
-                m_name: char::from_digit(old_state.id as u32, 36).unwrap()};
+            let sl = StateLabel{ id: old_state.id, m_name: old_m.label };
             State{
                 id: old_state.id,
                 label: sl,
@@ -247,3 +373,44 @@ impl Machine {
         Machine{ label: old_m.label, states: states.collect() }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::*;
+    use super::*;
+
+    #[test]
+    fn trim_removes_useless_states() {
+        let mut fsm = load_machine("tests/fsm_trim.txt");
+        assert_eq!(fsm.reachable_states_ids(), HashSet::from([0, 1, 2]));
+        assert_eq!(fsm.coreachable_states_ids(), HashSet::from([0, 1, 3]));
+        let orig = fsm.clone();
+        let mut removed = fsm.trim();
+        removed.sort();
+        assert_eq!(removed, vec![2, 3]);
+        assert_eq!(fsm.states_ids(), vec![0, 1]);
+        assert!(fsm.states.iter().all(|s| s.transitions.iter().all(|t| t.dest_id != 2)));
+        assert_equivalent(&fsm, &orig);
+    }
+
+    #[test]
+    fn trim_of_empty_language() {
+        let mut fsm = load_machine("tests/fsm_min_empty_lang.txt");
+        assert!(fsm.coreachable_states_ids().is_disjoint(&fsm.initial_states_ids().into_iter().collect()));
+        fsm.trim();
+        assert!(fsm.states.is_empty());
+    }
+
+    #[test]
+    fn renumber_after_removal() {
+        let mut fsm = load_machine("tests/fsm_trim.txt");
+        let orig = fsm.clone();
+        fsm.remove_states(&[0, 2]);
+        fsm.lookup_state_mut(3).is_initial = true;
+        assert_eq!(fsm.renumber(), vec![(1, 0), (3, 1)]);
+        assert_eq!(fsm.states_ids(), vec![0, 1]);
+        assert_eq!(fsm.lookup_state(1).transitions[0].dest_id, 0);
+        assert_eq!(fsm.lookup_state(1).label.id, 1);
+        assert_equivalent(&fsm, &orig);
+    }
+}
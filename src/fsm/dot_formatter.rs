@@ -22,6 +22,18 @@ impl DotFormat for NumTerm {
     }
 }
 
+impl DotFormat for GenMachineLabel {
+    fn to_dot(&self, _: bool) -> String {
+        format!("<{}<sub>{}</sub>>", self.name, self.gen_id)
+    }
+}
+
+impl Display for GenMachineLabel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.name, self.gen_id)
+    }
+}
+
 impl DotFormat for StateLabel {
     fn to_dot(&self, detailed: bool) -> String {
         let str_id = if self.id == -1 {
@@ -44,6 +44,47 @@ impl<ML: fmt::Display, SL, TL: fmt::Display> BaseMachine<ML, SL, TL> {
         }
         res
     }
+
+    pub fn validate_useful_states(&self) -> bool {
+        // Useless states are reported, but they do not make the machine invalid
+        let reachable = self.reachable_states_ids();
+        let coreachable = self.coreachable_states_ids();
+        for s in &self.states {
+            if !reachable.contains(&s.id) {
+                eprintln!("warning: state {}{} is unreachable", s.id, self.label);
+            } else if !coreachable.contains(&s.id) {
+                eprintln!("warning: no final state is reachable from state {}{}", s.id, self.label);
+            }
+        }
+        true
+    }
+}
+
+impl<ML: fmt::Display, SL> BaseMachine<ML, SL, char> {
+    pub fn validate_deterministic(&self) -> bool {
+        let mut res = true;
+        if self.initial_states_ids().len() > 1 {
+            eprintln!("error: multiple initial states in machine {}", self.label);
+            res = false;
+        }
+        for s in &self.states {
+            for (i, t) in s.transitions.iter().enumerate() {
+                if t.is_epsilon() {
+                    eprintln!("error: spontaneous transition {}{} -{}-> {}{}", s.id, self.label, t.label, t.dest_id, self.label);
+                    res = false;
+                } else if !s.transitions[..i].iter().any(|t2| t2.label == t.label) {
+                    let dests: Vec<_> = s.transitions[i..].iter().filter_map(|t2| {
+                        if t2.label == t.label { Some(format!("{}{}", t2.dest_id, self.label)) } else { None }
+                    }).collect();
+                    if dests.len() > 1 {
+                        eprintln!("error: multiple transitions {}{} -{}-> {}", s.id, self.label, t.label, dests.join(", "));
+                        res = false;
+                    }
+                }
+            }
+        }
+        res
+    }
 }
 
 impl<ML: fmt::Display, SL, TL: fmt::Display> Validation for BaseMachine<ML, SL, TL> {
@@ -52,7 +93,25 @@ impl<ML: fmt::Display, SL, TL: fmt::Display> Validation for BaseMachine<ML, SL,
             self.validate_state_count(),
             self.validate_any_initial_state(),
             self.validate_any_final_state(),
-            self.validate_transitions()
+            self.validate_transitions(),
+            self.validate_useful_states()
         ].into_iter().all(|v| v)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::thompson::*;
+    use crate::test_util::*;
+
+    #[test]
+    fn deterministic_validation_agrees_with_check() {
+        for file in ["tests/fsm_min_2025-02-12.txt", "tests/fsm_backprop_2015-02-09.txt", "tests/fsm_bs_2019-09-03.txt"] {
+            let fsm = load_machine(file);
+            assert_eq!(fsm.validate_deterministic(), fsm.is_deterministic(), "{file}");
+        }
+        let nfa = thompson(&regex("a*|ab"));
+        assert!(!nfa.validate_deterministic());
+        assert!(nfa.determinized().validate_deterministic());
+    }
+}
@@ -0,0 +1,78 @@
+use crate::reg_lang::*;
+use crate::fsm::*;
+
+// Each state is a position of the numbered expression, None being the initial one
+pub type GlushkovState = BaseState<Option<NumTerm>, char>;
+pub type GlushkovMachine = BaseMachine<char, Option<NumTerm>, char>;
+
+impl DotFormat for Option<NumTerm> {
+    fn to_dot(&self, detailed: bool) -> String {
+        match self {
+            Some(t) => t.to_dot(detailed),
+            None => "\"⊢\"".to_string()
+        }
+    }
+}
+
+// The position (local) automaton: state i is reached by reading the terminal
+// numbered i, and the initial state is 0
+pub fn glushkov<T: NumLocalSets>(x: &T) -> GlushkovMachine {
+    let mut res = GlushkovMachine::new('g');
+    let fin = x.numbered_finals();
+    let to_transition = |t: &NumTerm| Transition{ label: t.c, dest_id: t.i as i32 };
+
+    let mut ini: Vec<NumTerm> = x.numbered_initials().into_iter().collect();
+    ini.sort();
+    res.states.push(GlushkovState{
+        id: 0,
+        label: None,
+        transitions: ini.iter().map(to_transition).collect(),
+        is_initial: true,
+        is_final: x.nullable()
+    });
+
+    let mut followers: Vec<_> = x.numbered_followers().into_iter().collect();
+    followers.sort_by_key(|(t, _)| t.i);
+    for (t, fol) in followers {
+        let mut fol: Vec<NumTerm> = fol.into_iter().collect();
+        fol.sort();
+        res.states.push(GlushkovState{
+            id: t.i as i32,
+            label: Some(t),
+            transitions: fol.iter().map(to_transition).collect(),
+            is_initial: false,
+            is_final: fin.contains(&t)
+        });
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::berry_sethi::*;
+    use crate::test_util::*;
+    use super::*;
+
+    const REGEXES: [&str; 5] = ["(a|b)*abb", "(ab|_)(c|d)+", "a(ba)*|(ab)*a", "((a|b)*c)*", "_"];
+
+    #[test]
+    fn one_state_per_position() {
+        for re in REGEXES.map(regex) {
+            let m = glushkov(&re);
+            assert_eq!(m.states.len(), re.all_numbered().len() + 1);
+            assert_eq!(m.lookup_state(0).is_final, re.nullable());
+            for s in &m.states {
+                for t in &s.transitions {
+                    assert_eq!(m.lookup_state(t.dest_id).label.map(|p| p.c), Some(t.label));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn equivalent_to_berry_sethi() {
+        for re in REGEXES.map(regex) {
+            assert_equivalent(&Machine::from_machine(glushkov(&re)), &Machine::from_machine(berry_sethi(&re)));
+        }
+    }
+}
@@ -0,0 +1,241 @@
+mod validation;
+
+use std::collections::HashMap;
+use std::fmt;
+
+use itertools::Itertools;
+use crate::fsm::*;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    pub lhs: char,
+    pub rhs: Vec<char>
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Linearity {
+    Right,
+    Left
+}
+
+#[derive(Debug)]
+pub struct Grammar {
+    pub rules: Vec<Rule>
+}
+
+pub fn is_nonterminal(c: char) -> bool {
+    c.is_ascii_uppercase()
+}
+
+fn new_state(m: &mut Machine) -> i32 {
+    let id = m.states.len() as i32;
+    m.states.push(State{ id, label: StateLabel{ id, m_name: m.label }, transitions: vec![], is_initial: false, is_final: false });
+    id
+}
+
+impl Rule {
+    fn nonterminal_positions(&self) -> Vec<usize> {
+        self.rhs.iter().positions(|c| is_nonterminal(*c)).collect()
+    }
+
+    pub fn is_right_linear(&self) -> bool {
+        let pos = self.nonterminal_positions();
+        pos.is_empty() || pos == [self.rhs.len() - 1]
+    }
+
+    pub fn is_left_linear(&self) -> bool {
+        let pos = self.nonterminal_positions();
+        pos.is_empty() || pos == [0]
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.rhs.is_empty() {
+            write!(f, "{} -> _", self.lhs)
+        } else {
+            write!(f, "{} -> {}", self.lhs, self.rhs.iter().join(" "))
+        }
+    }
+}
+
+impl fmt::Display for Grammar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "grammar {{")?;
+        for lhs in self.nonterminals() {
+            let alts = self.rules.iter().filter(|r| r.lhs == lhs).map(|r| {
+                if r.rhs.is_empty() { "_".to_string() } else { r.rhs.iter().join(" ") }
+            }).join(" | ");
+            writeln!(f, "    {lhs} -> {alts};")?;
+        }
+        write!(f, "}}")
+    }
+}
+
+impl Grammar {
+    // The axiom comes first, followed by the others in order of definition
+    pub fn nonterminals(&self) -> Vec<char> {
+        let mut res: Vec<char> = vec![];
+        if self.rules.iter().any(|r| r.lhs == 'S') {
+            res.push('S');
+        }
+        for r in &self.rules {
+            if !res.contains(&r.lhs) {
+                res.push(r.lhs);
+            }
+        }
+        res
+    }
+
+    // Right linearity takes precedence when all rules are both right and left linear
+    pub fn linearity(&self) -> Option<Linearity> {
+        if self.rules.iter().all(|r| r.is_right_linear()) {
+            Some(Linearity::Right)
+        } else if self.rules.iter().all(|r| r.is_left_linear()) {
+            Some(Linearity::Left)
+        } else {
+            None
+        }
+    }
+
+    pub fn reversed(&self) -> Grammar {
+        let rules = self.rules.iter().map(|r| {
+            Rule{ lhs: r.lhs, rhs: r.rhs.iter().rev().cloned().collect() }
+        }).collect();
+        Grammar{ rules }
+    }
+
+    fn right_linear_to_machine(&self) -> Machine {
+        let mut res = Machine::new('G');
+        let state_of: HashMap<char, i32> = self.nonterminals().into_iter().enumerate().map(|(i, n)| (n, i as i32)).collect();
+        for _ in 0..state_of.len() {
+            new_state(&mut res);
+        }
+        res.lookup_state_mut(0).is_initial = true;
+        let mut final_id: Option<i32> = None;
+        for r in &self.rules {
+            let (terms, dest_id) = match r.rhs.split_last() {
+                Some((last, terms)) if is_nonterminal(*last) => (terms, state_of[last]),
+                _ if r.rhs.is_empty() => {
+                    res.lookup_state_mut(state_of[&r.lhs]).is_final = true;
+                    continue;
+                }
+                _ => {
+                    let id = *final_id.get_or_insert_with(|| new_state(&mut res));
+                    res.lookup_state_mut(id).is_final = true;
+                    (&r.rhs[..], id)
+                }
+            };
+            let mut src_id = state_of[&r.lhs];
+            if terms.is_empty() {
+                res.lookup_state_mut(src_id).transitions.push(Transition{ label: '_', dest_id });
+                continue;
+            }
+            for (i, c) in terms.iter().enumerate() {
+                let next_id = if i == terms.len() - 1 { dest_id } else { new_state(&mut res) };
+                res.lookup_state_mut(src_id).transitions.push(Transition{ label: *c, dest_id: next_id });
+                src_id = next_id;
+            }
+        }
+        res
+    }
+
+    pub fn to_machine(&self) -> Machine {
+        if self.linearity() == Some(Linearity::Left) {
+            self.reversed().right_linear_to_machine().reverse()
+        } else {
+            self.right_linear_to_machine()
+        }
+    }
+
+    fn right_linear_from_machine(m: &Machine) -> Option<Grammar> {
+        // Useless states would become nonterminals without rules
+        let mut m = m.clone();
+        m.trim();
+        if m.states.is_empty() {
+            // The axiom of the empty language never derives a terminal string
+            return Some(Grammar{ rules: vec![Rule{ lhs: 'S', rhs: vec!['S'] }] });
+        }
+        let initials = m.initial_states_ids();
+        let mut names = ('A'..='Z').filter(|c| *c != 'S');
+        let mut name_of: HashMap<i32, char> = HashMap::new();
+        for s in &m.states {
+            let name = if initials == [s.id] { Some('S') } else { names.next() };
+            name_of.insert(s.id, name?);
+        }
+        let mut rules: Vec<Rule> = vec![];
+        if initials.len() != 1 {
+            rules.extend(initials.iter().map(|sid| Rule{ lhs: 'S', rhs: vec![name_of[sid]] }));
+        }
+        for s in &m.states {
+            let lhs = name_of[&s.id];
+            for t in &s.transitions {
+                let rhs = if t.is_epsilon() { vec![name_of[&t.dest_id]] } else { vec![t.label, name_of[&t.dest_id]] };
+                rules.push(Rule{ lhs, rhs });
+            }
+            if s.is_final {
+                rules.push(Rule{ lhs, rhs: vec![] });
+            }
+        }
+        Some(Grammar{ rules })
+    }
+
+    // Fails when the machine has more states than available nonterminal names
+    pub fn from_machine(m: &Machine, linearity: Linearity) -> Option<Grammar> {
+        match linearity {
+            Linearity::Right => Grammar::right_linear_from_machine(m),
+            Linearity::Left => Some(Grammar::right_linear_from_machine(&m.reverse())?.reversed())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use crate::lexer::*;
+    use crate::parser::*;
+    use crate::validation::*;
+    use crate::test_util::*;
+    use super::*;
+
+    fn load_grammar(file: &str) -> Grammar {
+        Parser::new(Lexer::from_path(Path::new(file))).parse_grammar_file().unwrap()
+    }
+
+    #[test]
+    fn grammar_to_machine() {
+        let g = load_grammar("tests/grammar_right_linear.txt");
+        assert_eq!(g.linearity(), Some(Linearity::Right));
+        assert_equivalent(&g.to_machine(), &regex_machine("a*b(c|_)"));
+        let g = load_grammar("tests/grammar_left_linear.txt");
+        assert_eq!(g.linearity(), Some(Linearity::Left));
+        assert_equivalent(&g.to_machine(), &regex_machine("a*b|a"));
+    }
+
+    #[test]
+    fn printed_grammar_round_trip() {
+        let path = std::env::temp_dir().join(format!("flc_toolkit_grammar_{}.txt", std::process::id()));
+        let files = ["tests/fsm_min_empty_lang.txt", "tests/fsm_trim.txt", "tests/fsm_backprop_2015-02-09.txt", "tests/fsm_bs_2019-09-03.txt"];
+        for m in files.map(load_machine) {
+            for linearity in [Linearity::Right, Linearity::Left] {
+                std::fs::write(&path, Grammar::from_machine(&m, linearity).unwrap().to_string()).unwrap();
+                let g = load_grammar(path.to_str().unwrap());
+                assert!(g.validate(), "{g}");
+                assert_equivalent(&g.to_machine(), &m);
+            }
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn machine_to_grammar_round_trip() {
+        for re in ["(a|b)*abb", "(ab|_)(c|d)+", "_"] {
+            let m = regex_machine(re);
+            for linearity in [Linearity::Right, Linearity::Left] {
+                let g = Grammar::from_machine(&m, linearity).unwrap();
+                assert!(g.linearity().is_some());
+                assert_equivalent(&g.to_machine(), &m);
+            }
+        }
+    }
+}
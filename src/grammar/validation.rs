@@ -0,0 +1,66 @@
+use crate::grammar::*;
+use crate::validation::*;
+
+impl Grammar {
+    fn validate_rule_count(&self) -> bool {
+        if self.rules.is_empty() {
+            eprintln!("error: no rules in the grammar");
+            false
+        } else {
+            true
+        }
+    }
+
+    fn validate_start(&self) -> bool {
+        // There must be rules for the axiom S
+        if !self.rules.iter().any(|r| r.lhs == 'S') {
+            eprintln!("error: axiom (nonterminal S) missing");
+            false
+        } else {
+            true
+        }
+    }
+
+    fn validate_nonterminals(&self) -> bool {
+        let mut res = true;
+        for r in &self.rules {
+            if !is_nonterminal(r.lhs) {
+                eprintln!("error: left hand side of rule {r} is not a nonterminal");
+                res = false;
+            }
+            for c in &r.rhs {
+                if is_nonterminal(*c) && !self.rules.iter().any(|r2| r2.lhs == *c) {
+                    eprintln!("error: nonterminal {c} in rule {r} has no rules");
+                    res = false;
+                }
+            }
+        }
+        res
+    }
+
+    fn validate_linearity(&self) -> bool {
+        let mut res = true;
+        for r in &self.rules {
+            if !r.is_right_linear() && !r.is_left_linear() {
+                eprintln!("error: rule {r} is neither right-linear nor left-linear");
+                res = false;
+            }
+        }
+        if res && self.linearity().is_none() {
+            eprintln!("error: the grammar mixes right-linear and left-linear rules");
+            res = false;
+        }
+        res
+    }
+}
+
+impl Validation for Grammar {
+    fn validate(&self) -> bool {
+        [
+            self.validate_rule_count(),
+            self.validate_start(),
+            self.validate_nonterminals(),
+            self.validate_linearity()
+        ].into_iter().all(|v| v)
+    }
+}
@@ -0,0 +1,140 @@
+use std::collections::*;
+
+use itertools::Itertools;
+use crate::fsm::*;
+
+struct Partition {
+    ids: Vec<i32>,
+    blocks: Vec<Vec<usize>>,
+    block_of: Vec<usize>
+}
+
+impl Partition {
+    // The last index stands for the implicit sink state
+    fn format_block(&self, block: &[usize]) -> String {
+        let body = block.iter().sorted().map(|i| {
+            self.ids.get(*i).map_or("⊥".to_string(), |id| id.to_string())
+        }).join(", ");
+        format!("{{{body}}}")
+    }
+
+    fn split(&mut self, b: usize, members: &[usize]) -> usize {
+        let new_b = self.blocks.len();
+        self.blocks[b].retain(|i| !members.contains(i));
+        for i in members {
+            self.block_of[*i] = new_b;
+        }
+        self.blocks.push(members.to_vec());
+        new_b
+    }
+}
+
+impl Machine {
+    pub fn hopcroft_equiv_sets(&self) -> Vec<HashSet<i32>> {
+        let ids = self.states_ids();
+        let sink = ids.len();
+        let index_of: HashMap<i32, usize> = ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+        let alphabet = self.alphabet();
+
+        // inverse[c][j] lists the states going to state j with character c
+        let mut inverse: Vec<Vec<Vec<usize>>> = vec![vec![vec![]; sink + 1]; alphabet.len()];
+        for (ci, c) in alphabet.iter().enumerate() {
+            for (i, id) in ids.iter().enumerate() {
+                let j = self.next_state(*id, *c).map_or(sink, |dest_id| index_of[&dest_id]);
+                inverse[ci][j].push(i);
+            }
+            inverse[ci][sink].push(sink);
+        }
+
+        let (finals, non_finals): (Vec<usize>, Vec<usize>) = (0..=sink).partition(|i| {
+            *i != sink && self.lookup_state(ids[*i]).is_final
+        });
+        let mut part = Partition{ ids, blocks: vec![], block_of: vec![0; sink + 1] };
+        for block in [finals, non_finals] {
+            if !block.is_empty() {
+                let b = part.blocks.len();
+                for i in &block {
+                    part.block_of[*i] = b;
+                }
+                part.blocks.push(block);
+            }
+        }
+
+        let smallest = (0..part.blocks.len()).min_by_key(|b| part.blocks[*b].len()).unwrap();
+        let mut worklist: VecDeque<(usize, usize)> = (0..alphabet.len()).map(|ci| (smallest, ci)).collect();
+        let mut in_worklist: HashSet<(usize, usize)> = worklist.iter().cloned().collect();
+        while let Some((splitter, ci)) = worklist.pop_front() {
+            in_worklist.remove(&(splitter, ci));
+            let splitter_str = part.format_block(&part.blocks[splitter]);
+            let preds: BTreeSet<usize> = part.blocks[splitter].iter().flat_map(|j| {
+                inverse[ci][*j].iter().cloned()
+            }).collect();
+            let mut touched: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+            for i in preds {
+                touched.entry(part.block_of[i]).or_default().push(i);
+            }
+            for (b, members) in touched {
+                if members.len() == part.blocks[b].len() {
+                    continue;
+                }
+                let old_str = part.format_block(&part.blocks[b]);
+                let new_b = part.split(b, &members);
+                eprintln!("{old_str} split into {} and {} by {}-transitions into {splitter_str}",
+                    part.format_block(&part.blocks[new_b]), part.format_block(&part.blocks[b]), alphabet[ci]);
+                for cj in 0..alphabet.len() {
+                    if in_worklist.contains(&(b, cj)) || part.blocks[new_b].len() <= part.blocks[b].len() {
+                        worklist.push_back((new_b, cj));
+                        in_worklist.insert((new_b, cj));
+                    } else {
+                        worklist.push_back((b, cj));
+                        in_worklist.insert((b, cj));
+                    }
+                }
+            }
+        }
+
+        part.blocks.iter().filter_map(|block| {
+            let set: HashSet<i32> = block.iter().filter_map(|i| part.ids.get(*i).cloned()).collect();
+            if set.is_empty() { None } else { Some(set) }
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::nerode::*;
+    use crate::test_util::*;
+    use super::*;
+
+    fn normalized(sets: &[HashSet<i32>]) -> Vec<Vec<i32>> {
+        sets.iter().filter(|s| !s.is_empty()).map(|s| s.iter().cloned().sorted().collect::<Vec<_>>()).sorted().collect()
+    }
+
+    fn trimmed_dfas() -> Vec<Machine> {
+        let mut res: Vec<Machine> = ["tests/fsm_min_2025-02-12.txt", "tests/fsm_min_2025-06-05.txt", "tests/fsm_min_2025-07-09.txt"].map(load_machine).into();
+        res.extend(["(a|b)*abb", "(ab|_)(c|d)+", "a(ba)*|(ab)*a"].map(|re| regex_machine(re).determinized()));
+        for m in &mut res {
+            m.trim();
+        }
+        res
+    }
+
+    #[test]
+    fn same_partition_as_nerode() {
+        for fsm in trimmed_dfas() {
+            let mut dist = fsm.dist_table_len_0();
+            while fsm.dist_table_update(&mut dist) > 0 { }
+            assert_eq!(normalized(&fsm.hopcroft_equiv_sets()), normalized(&fsm.cliques(&dist)));
+        }
+    }
+
+    #[test]
+    fn minimized_machine_is_equivalent() {
+        for fsm in trimmed_dfas() {
+            let m = Machine::from_machine(MinimizedMachine::from_machine_and_equiv_sets(&fsm, &fsm.hopcroft_equiv_sets()));
+            assert!(m.is_deterministic());
+            assert_eq!(m.words(8, 100), fsm.words(8, 100));
+            assert_equivalent(&m, &fsm);
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use std::collections::*;
+
+use crate::fsm::*;
+
+pub struct LangInfo {
+    pub is_empty: bool,
+    pub is_finite: bool,
+    pub cardinality: Option<u128>,
+    pub counts_by_len: Vec<u128>,
+    pub shortest_word: Option<String>,
+    pub longest_word: Option<String>
+}
+
+fn has_cycle_from(dfa: &Machine, sid: i32, on_stack: &mut HashSet<i32>, done: &mut HashSet<i32>) -> bool {
+    if on_stack.contains(&sid) {
+        return true;
+    }
+    if done.contains(&sid) {
+        return false;
+    }
+    on_stack.insert(sid);
+    let res = dfa.lookup_state(sid).transitions.iter().any(|t| has_cycle_from(dfa, t.dest_id, on_stack, done));
+    on_stack.remove(&sid);
+    done.insert(sid);
+    res
+}
+
+// Length of the longest path to a final state, for machines without cycles
+fn longest_from(dfa: &Machine, sid: i32, memo: &mut HashMap<i32, usize>) -> usize {
+    if let Some(len) = memo.get(&sid) {
+        return *len;
+    }
+    let s = dfa.lookup_state(sid);
+    let len = s.transitions.iter().map(|t| longest_from(dfa, t.dest_id, memo) + 1).max().unwrap_or(0);
+    memo.insert(sid, len);
+    len
+}
+
+impl Machine {
+    pub fn lang_info(&self, max_len: usize) -> LangInfo {
+        let mut dfa = self.determinized();
+        dfa.trim();
+        let ini = dfa.initial_states_ids().first().cloned();
+        let is_empty = ini.is_none();
+
+        let mut done: HashSet<i32> = HashSet::new();
+        let is_finite = ini.is_none_or(|sid| !has_cycle_from(&dfa, sid, &mut HashSet::new(), &mut done));
+
+        // Counting paths is enough as the machine is deterministic
+        let count_up_to = if is_finite { max_len.max(dfa.states.len()) } else { max_len };
+        let mut counts_by_len: Vec<u128> = vec![];
+        let mut paths: HashMap<i32, u128> = ini.into_iter().map(|sid| (sid, 1)).collect();
+        for _ in 0..=count_up_to {
+            counts_by_len.push(paths.iter().filter(|(sid, _)| dfa.lookup_state(**sid).is_final).fold(0u128, |acc, (_, n)| acc.saturating_add(*n)));
+            let mut next: HashMap<i32, u128> = HashMap::new();
+            for (sid, n) in &paths {
+                for t in &dfa.lookup_state(*sid).transitions {
+                    let e = next.entry(t.dest_id).or_insert(0);
+                    *e = e.saturating_add(*n);
+                }
+            }
+            paths = next;
+        }
+        let cardinality = if is_finite {
+            Some(counts_by_len.iter().fold(0u128, |acc, n| acc.saturating_add(*n)))
+        } else {
+            None
+        };
+        counts_by_len.truncate(max_len + 1);
+
+        let longest_word = ini.filter(|_| is_finite).map(|ini| {
+            let mut memo: HashMap<i32, usize> = HashMap::new();
+            let mut word = String::new();
+            let mut sid = ini;
+            let mut len = longest_from(&dfa, sid, &mut memo);
+            while len > 0 {
+                let mut ts: Vec<_> = dfa.lookup_state(sid).transitions.iter().collect();
+                ts.sort_by_key(|t| t.label);
+                let t = ts.into_iter().find(|t| longest_from(&dfa, t.dest_id, &mut memo) + 1 == len).unwrap();
+                word.push(t.label);
+                sid = t.dest_id;
+                len -= 1;
+            }
+            word
+        });
+
+        LangInfo{
+            is_empty,
+            is_finite,
+            cardinality,
+            counts_by_len,
+            shortest_word: dfa.shortest_word(),
+            longest_word
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::*;
+
+    #[test]
+    fn finite_language() {
+        let info = regex_machine("(a|b)(c|_)").lang_info(3);
+        assert!(!info.is_empty && info.is_finite);
+        assert_eq!(info.cardinality, Some(4));
+        assert_eq!(info.counts_by_len, vec![0, 2, 2, 0]);
+        assert_eq!(info.shortest_word.as_deref(), Some("a"));
+        assert_eq!(info.longest_word.as_deref(), Some("ac"));
+    }
+
+    #[test]
+    fn infinite_and_empty_languages() {
+        let info = regex_machine("(ab)*").lang_info(4);
+        assert!(!info.is_finite);
+        assert_eq!(info.cardinality, None);
+        assert_eq!(info.counts_by_len, vec![1, 0, 1, 0, 1]);
+        assert_eq!(info.longest_word, None);
+        let info = load_machine("tests/fsm_min_empty_lang.txt").lang_info(4);
+        assert!(info.is_empty && info.is_finite);
+        assert_eq!(info.cardinality, Some(0));
+        assert_eq!(info.shortest_word, None);
+    }
+
+    #[test]
+    fn counts_agree_with_words() {
+        let fsm = regex_machine("(a|bc)*(d|_)");
+        let words = fsm.words(6, usize::MAX);
+        for (len, count) in fsm.lang_info(6).counts_by_len.into_iter().enumerate() {
+            assert_eq!(words.iter().filter(|w| w.len() == len).count() as u128, count);
+        }
+    }
+}
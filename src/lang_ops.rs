@@ -0,0 +1,132 @@
+use std::collections::*;
+
+use itertools::Itertools;
+use crate::fsm::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Closure {
+    Prefix,
+    Suffix,
+    Factor
+}
+
+impl Machine {
+    // Pairs of states of the two machines reachable from the given ones by reading
+    // the same word
+    fn pairs_reachable_from(&self, other: &Machine, start: Vec<(i32, i32)>) -> HashSet<(i32, i32)> {
+        let mut visited: HashSet<(i32, i32)> = HashSet::new();
+        let mut worklist = VecDeque::from(start);
+        while let Some((p, q)) = worklist.pop_front() {
+            if !visited.insert((p, q)) {
+                continue;
+            }
+            let (sp, sq) = (self.lookup_state(p), other.lookup_state(q));
+            for t in &sp.transitions {
+                if t.is_epsilon() {
+                    worklist.push_back((t.dest_id, q));
+                } else {
+                    worklist.extend(sq.transitions.iter().filter(|t2| t2.label == t.label).map(|t2| (t.dest_id, t2.dest_id)));
+                }
+            }
+            worklist.extend(sq.transitions.iter().filter(|t2| t2.is_epsilon()).map(|t2| (p, t2.dest_id)));
+        }
+        visited
+    }
+
+    // The new initial states are the ones reached by reading a word of the other machine
+    pub fn left_quotient(&self, other: &Machine) -> Machine {
+        let start = self.initial_states_ids().into_iter().cartesian_product(other.initial_states_ids()).collect();
+        let reach = self.pairs_reachable_from(other, start);
+        let mut res = self.clone();
+        for s in &mut res.states {
+            s.is_initial = reach.iter().any(|(p, q)| *p == s.id && other.lookup_state(*q).is_final);
+        }
+        res
+    }
+
+    // The new final states are the ones from which a final state is reached by
+    // reading a word of the other machine
+    pub fn right_quotient(&self, other: &Machine) -> Machine {
+        let mut res = self.clone();
+        for s in &mut res.states {
+            let start = other.initial_states_ids().into_iter().map(|q| (s.id, q)).collect();
+            let reach = self.pairs_reachable_from(other, start);
+            s.is_final = reach.iter().any(|(p, q)| self.lookup_state(*p).is_final && other.lookup_state(*q).is_final);
+        }
+        res
+    }
+
+    pub fn closure(&self, kind: Closure) -> Machine {
+        let mut res = self.clone();
+        res.trim();
+        for s in &mut res.states {
+            s.is_final |= kind != Closure::Suffix;
+            s.is_initial |= kind != Closure::Prefix;
+        }
+        res
+    }
+
+    // Each transition on a substituted letter is replaced by a copy of the
+    // corresponding machine, connected by spontaneous transitions
+    pub fn substitute(&self, images: &HashMap<char, Machine>) -> Machine {
+        let mut res = Machine::new(self.label);
+        let mut next_id = self.states.iter().map(|s| s.id + 1).max().unwrap_or(0);
+        let mut copies: Vec<State> = vec![];
+        for s in &self.states {
+            let mut transitions: Vec<Transition> = vec![];
+            for t in &s.transitions {
+                let Some(image) = images.get(&t.label) else {
+                    transitions.push(*t);
+                    continue;
+                };
+                let offset = next_id - image.states.iter().map(|s2| s2.id).min().unwrap_or(0);
+                for s2 in &image.states {
+                    let id = s2.id + offset;
+                    let mut copy_transitions: Vec<Transition> = s2.transitions.iter().map(|t2| {
+                        Transition{ label: t2.label, dest_id: t2.dest_id + offset }
+                    }).collect();
+                    if s2.is_final {
+                        copy_transitions.push(Transition{ label: '_', dest_id: t.dest_id });
+                    }
+                    if s2.is_initial {
+                        transitions.push(Transition{ label: '_', dest_id: id });
+                    }
+                    copies.push(State{ id, label: StateLabel{ id, m_name: self.label }, transitions: copy_transitions, is_initial: false, is_final: false });
+                    next_id = next_id.max(id + 1);
+                }
+            }
+            res.states.push(State{ id: s.id, label: s.label, transitions, is_initial: s.is_initial, is_final: s.is_final });
+        }
+        res.states.extend(copies);
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::*;
+    use super::*;
+
+    #[test]
+    fn quotients() {
+        let m = regex_machine("abc|abd|bd");
+        assert_equivalent(&m.left_quotient(&regex_machine("ab")), &regex_machine("c|d"));
+        assert_equivalent(&m.left_quotient(&regex_machine("a*")), &regex_machine("abc|abd|bc|bd"));
+        assert_equivalent(&m.right_quotient(&regex_machine("d")), &regex_machine("ab|b"));
+        assert!(m.right_quotient(&regex_machine("cd")).shortest_word().is_none());
+    }
+
+    #[test]
+    fn closures() {
+        let m = regex_machine("ab");
+        assert_eq!(m.closure(Closure::Prefix).words(2, 10), vec!["", "a", "ab"]);
+        assert_eq!(m.closure(Closure::Suffix).words(2, 10), vec!["", "b", "ab"]);
+        assert_eq!(m.closure(Closure::Factor).words(2, 10), vec!["", "a", "b", "ab"]);
+    }
+
+    #[test]
+    fn substitution() {
+        let images = HashMap::from([('a', regex_machine("cd|_")), ('b', regex_machine("e+"))]);
+        assert_equivalent(&regex_machine("(ab)*").substitute(&images), &regex_machine("((cd|_)e+)*"));
+    }
+}
@@ -21,11 +21,13 @@ pub enum TokenValue {
     LBrace,
     RBrace,
     RArrow,
+    Bar,
     KwMNet,
     KwMachine,
     KwState,
     KwInitial,
     KwFinal,
+    KwGrammar,
     EndOfFile
 }
 
@@ -179,6 +181,8 @@ impl Iterator for Lexer {
             return Some(Token::from_frag(&frag, TokenValue::RBrace));
         } else if let Some(frag) = self.accept_pattern("->") {
             return Some(Token::from_frag(&frag, TokenValue::RArrow));
+        } else if let Some(frag) = self.accept_pattern("|") {
+            return Some(Token::from_frag(&frag, TokenValue::Bar));
         } else if let Some(frag) = self.accept_identifier() {
             let id = frag.val;
             if id == "mnet" {
@@ -191,6 +195,8 @@ impl Iterator for Lexer {
                 return Some(Token::from_frag(&frag, TokenValue::KwInitial));
             } else if id == "final" {
                 return Some(Token::from_frag(&frag, TokenValue::KwFinal));
+            } else if id == "grammar" {
+                return Some(Token::from_frag(&frag, TokenValue::KwGrammar));
             } else if id.len() == 1 {
                 return Some(Token::from_frag(&frag, TokenValue::Ident(id.chars().next().unwrap())));
             } else {
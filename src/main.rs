@@ -10,7 +10,25 @@ mod berry_sethi;
 mod bmc;
 mod epsilon_elim;
 mod nerode;
+mod subset;
+mod hopcroft;
+mod thompson;
+mod derivatives;
+mod equivalence;
+mod product;
+mod simulation;
+mod words;
+mod lang_info;
+mod reversal;
+mod grammar;
+mod lang_ops;
+mod ext_regex;
+mod ambiguity;
+mod glushkov;
+#[cfg(test)]
+mod test_util;
 
+use std::collections::HashMap;
 use std::path::Path;
 use std::process::ExitCode;
 use itertools::Itertools;
@@ -24,6 +42,19 @@ pub use crate::validation::*;
 pub use crate::elr_pilot::*;
 pub use crate::berry_sethi::*;
 pub use crate::bmc::*;
+pub use crate::bmc::search::*;
+pub use crate::regex::metrics::*;
+pub use crate::subset::*;
+pub use crate::thompson::*;
+pub use crate::derivatives::*;
+pub use crate::equivalence::*;
+pub use crate::product::*;
+pub use crate::simulation::*;
+pub use crate::grammar::*;
+pub use crate::lang_ops::*;
+pub use crate::ext_regex::*;
+pub use crate::ambiguity::*;
+pub use crate::glushkov::*;
 
 enum CmdError {
     BadArgs,
@@ -64,18 +95,46 @@ fn help() {
     eprintln!("    Prints the FSM in <file> to the standard output stream in graphviz dot");
     eprintln!("    format.");
     eprintln!();
-    eprintln!("  berry_sethi <regex>");
+    eprintln!("  berry_sethi <regex> [-m|--minimize] [-s|--snf]");
     eprintln!("    Converts the given <regex> to a finite state machine using the Berry-Sethi");
     eprintln!("    algorithm, and then prints it to the standard output stream in graphviz dot");
     eprintln!("    format. The sets of initials and followers are also printed to the standard");
+    eprintln!("    error stream. The operands of complements and intersections are converted");
+    eprintln!("    separately and combined with the product and complement constructions.");
+    eprintln!("    Options:");
+    eprintln!("      -m|--minimize   Minimizes the resulting FSM.");
+    eprintln!("      -s|--snf        Converts <regex> to star normal form first (not");
+    eprintln!("                      available with complement and intersection).");
+    eprintln!();
+    eprintln!("  glushkov <regex>");
+    eprintln!("    Converts the given <regex> to its position (Glushkov) automaton, with one");
+    eprintln!("    state for each numbered terminal plus the initial state, and then prints");
+    eprintln!("    it to the standard output stream in graphviz dot format. The sets of");
+    eprintln!("    initials and followers are also printed to the standard error stream.");
+    eprintln!();
+    eprintln!("  snf <regex>");
+    eprintln!("    Converts <regex> to star normal form (Bruggemann-Klein), where no follower");
+    eprintln!("    is added more than once by the iterations, and then prints it to the");
+    eprintln!("    standard output stream. Both numbered regexes are printed to the standard");
     eprintln!("    error stream.");
     eprintln!();
+    eprintln!("  thompson <regex>");
+    eprintln!("    Converts the given <regex> to a finite state machine with spontaneous moves");
+    eprintln!("    using the Thompson (McNaughton-Yamada-Thompson) construction, and then");
+    eprintln!("    prints it to the standard output stream in graphviz dot format.");
+    eprintln!();
+    eprintln!("  derivatives <regex>");
+    eprintln!("    Converts the given <regex> to a deterministic finite state machine using");
+    eprintln!("    Brzozowski derivatives, and then prints it to the standard output stream in");
+    eprintln!("    graphviz dot format. Each state is labeled by its residual regex, and the");
+    eprintln!("    derivatives are also printed to the standard error stream.");
+    eprintln!();
     eprintln!("  berry_sethi_fsm <file>");
     eprintln!("    Determinizes the FSM in <file> using the Berry-Sethi algorithm, and then");
     eprintln!("    prints it to the standard output stream in graphviz dot format. The sets of");
     eprintln!("    initials and followers are also printed to the standard error stream.");
     eprintln!();
-    eprintln!("  bmc <file> [-o|--order <list>]");
+    eprintln!("  bmc <file> [-o|--order <list>] [-m|--metric <metric>]");
     eprintln!("    Converts the FSM in <file> to a regular expression using the Brzozowski-");
     eprintln!("    McCluskey (node elimination) method. The reduction steps are logged to the");
     eprintln!("    standard error stream, and the intermediate and final steps of the reduction");
@@ -85,19 +144,185 @@ fn help() {
     eprintln!("                          comma-separated list. For example '3,2,1' forces the");
     eprintln!("                          elimination of state 3 first, followed by states 2 and");
     eprintln!("                          1. Any other state left is not eliminated.");
+    eprintln!("                          With 'best', searches for the order producing the");
+    eprintln!("                          smallest regular expression according to the metric.");
+    eprintln!("      -m|--metric <metric>");
+    eprintln!("                          Selects the size metric used by '--order best':");
+    eprintln!("                          'len' (default, string length), 'literals' (number");
+    eprintln!("                          of literals), 'size' (literals, _ and operators) or");
+    eprintln!("                          'star' (star height).");
     eprintln!();
-    eprintln!("  minimize <file>");
+    eprintln!("  minimize <file> [-a|--algo <algorithm>] [-d|--determinize]");
     eprintln!("    Minimizes the FSM in <file> using the Nerode-McCluskey algorithm, and then");
     eprintln!("    prints it to the standard output stream in graphviz dot format. The process");
     eprintln!("    of determining the set of distinguishable states is also printed to the");
-    eprintln!("    standard error stream.");
+    eprintln!("    standard error stream. Useless states are removed before minimization.");
+    eprintln!("    Nondeterministic FSMs are rejected unless --determinize is specified.");
+    eprintln!("    Options:");
+    eprintln!("      -a|--algo <algorithm>  Selects the minimization algorithm: 'nerode'");
+    eprintln!("                             (default) or 'hopcroft' (partition refinement,");
+    eprintln!("                             logs each split of a class of states) or");
+    eprintln!("                             'brzozowski' (determinizes the reversal twice,");
+    eprintln!("                             also accepts nondeterministic FSMs).");
+    eprintln!("      -d|--determinize       Eliminates spontaneous moves by backward");
+    eprintln!("                             propagation and determinizes the FSM using the");
+    eprintln!("                             subset construction before minimizing it.");
+    eprintln!();
+    eprintln!("  trim <file>");
+    eprintln!("    Removes the unreachable states and the states that cannot reach a final");
+    eprintln!("    state from the FSM in <file>, and then prints it to the standard output");
+    eprintln!("    stream in graphviz dot format.");
+    eprintln!();
+    eprintln!("  determinize <file>");
+    eprintln!("    Determinizes the FSM in <file> using the subset construction, and then");
+    eprintln!("    prints it to the standard output stream in graphviz dot format. Each subset");
+    eprintln!("    of states of the original FSM is also printed to the standard error stream.");
+    eprintln!();
+    eprintln!("  regex_info <regex>");
+    eprintln!("    Prints to the standard output stream the size of <regex>, its star height,");
+    eprintln!("    the number of operators and of literals (occurrences of letters, also known");
+    eprintln!("    as alphabetic width), its alphabet, and whether it is in star normal form.");
+    eprintln!();
+    eprintln!("  ambiguous_regex <regex>");
+    eprintln!("    Checks whether <regex> is ambiguous, that is whether two distinct strings");
+    eprintln!("    of the numbered regex are the same word once the numbers are erased. If so,");
+    eprintln!("    a shortest ambiguous word is printed to the standard output stream with");
+    eprintln!("    both numberings.");
+    eprintln!();
+    eprintln!("  simplify_regex <regex>");
+    eprintln!("    Simplifies <regex> by applying algebraic identities such as _ r = r,");
+    eprintln!("    r | r = r, (r*)* = r*, _ | r+ = r* and r r* = r+, and then prints it to the");
+    eprintln!("    standard output stream.");
+    eprintln!();
+    eprintln!("  reverse <file>");
+    eprintln!("    Reverses the FSM in <file> by swapping initial and final states and the");
+    eprintln!("    direction of all transitions, and then prints it to the standard output");
+    eprintln!("    stream in graphviz dot format.");
+    eprintln!();
+    eprintln!("  reverse_regex <regex>");
+    eprintln!("    Prints the mirror image of <regex> to the standard output stream.");
+    eprintln!();
+    eprintln!("  left_quotient <file> <regex>");
+    eprintln!("  right_quotient <file> <regex>");
+    eprintln!("    Computes the left (or right) quotient of the language of the FSM in <file>");
+    eprintln!("    by the language of <regex>, that is the set of words x such that yx (or xy)");
+    eprintln!("    is accepted for some word y of <regex>. The resulting FSM is then printed to");
+    eprintln!("    the standard output stream in graphviz dot format.");
+    eprintln!();
+    eprintln!("  prefixes <file>");
+    eprintln!("  suffixes <file>");
+    eprintln!("  factors <file>");
+    eprintln!("    Computes an FSM accepting all the prefixes (or suffixes, or factors) of the");
+    eprintln!("    words accepted by the FSM in <file>, and then prints it to the standard");
+    eprintln!("    output stream in graphviz dot format. Useless states are removed first.");
+    eprintln!();
+    eprintln!("  substitute <file> [-s|--subst <letter>=<regex> ...]");
+    eprintln!("    Replaces each transition of the FSM in <file> labeled by a substituted");
+    eprintln!("    letter with a copy of the Berry-Sethi FSM of the corresponding regex, and");
+    eprintln!("    then prints the result to the standard output stream in graphviz dot");
+    eprintln!("    format. The --subst option can be repeated.");
+    eprintln!();
+    eprintln!("  equiv <file1> <file2>");
+    eprintln!("    Checks whether the FSMs in <file1> and <file2> accept the same language. If");
+    eprintln!("    they do not, the shortest string accepted by only one of them is printed.");
+    eprintln!();
+    eprintln!("  equiv_regex <regex1> <regex2>");
+    eprintln!("  equiv_regex_fsm <regex> <file>");
+    eprintln!("    Checks whether <regex1> and <regex2>, or <regex> and the FSM in <file>,");
+    eprintln!("    denote the same language. The regular expressions are converted to FSMs");
+    eprintln!("    using the Berry-Sethi algorithm. If the languages differ, the shortest");
+    eprintln!("    string belonging to only one of them is printed.");
+    eprintln!();
+    eprintln!("  accepts <file> <word> [--all-runs]");
+    eprintln!("    Simulates the FSM in <file> on <word>, printing the set of active states");
+    eprintln!("    after each character. The exit status is zero if and only if <word> is");
+    eprintln!("    accepted. Use '_' or an empty argument for the empty word.");
+    eprintln!("    Options:");
+    eprintln!("      --all-runs   Also prints every accepting path of the FSM on <word>.");
+    eprintln!();
+    eprintln!("  words <file> [--max-len <n>] [--limit <k>]");
+    eprintln!("  words_regex <regex> [--max-len <n>] [--limit <k>]");
+    eprintln!("    Prints the strings accepted by the FSM in <file>, or denoted by <regex>, in");
+    eprintln!("    length-lexicographic order. The regular expression is converted to a FSM");
+    eprintln!("    using the Berry-Sethi algorithm.");
+    eprintln!("    Options:");
+    eprintln!("      --max-len <n>   Only strings of length up to <n> are printed (default 10).");
+    eprintln!("      --limit <k>     At most <k> strings are printed (default 20).");
+    eprintln!();
+    eprintln!("  lang_info <file> [--max-len <n>]");
+    eprintln!("  lang_info_regex <regex> [--max-len <n>]");
+    eprintln!("    Prints whether the language of the FSM in <file>, or denoted by <regex>, is");
+    eprintln!("    empty or finite, its cardinality, its shortest and (if finite) longest");
+    eprintln!("    strings, and the number of strings of each length up to <n> (default 10).");
+    eprintln!("    The regular expression is converted to a FSM using the Berry-Sethi");
+    eprintln!("    algorithm.");
     eprintln!();
-    eprintln!("  backprop <file>");
-    eprintln!("  forwardprop <file>");
+    eprintln!("  includes <file1> <file2>");
+    eprintln!("    Checks whether the language of the FSM in <file1> is included in the");
+    eprintln!("    language of the FSM in <file2>. If it is not, the shortest string accepted");
+    eprintln!("    by the first FSM and not by the second one is printed, together with the");
+    eprintln!("    sets of active states of both FSMs after each character.");
+    eprintln!();
+    eprintln!("  intersect <file1> <file2>");
+    eprintln!("  union <file1> <file2>");
+    eprintln!("  difference <file1> <file2>");
+    eprintln!("    Computes the product of the FSMs in <file1> and <file2> recognizing the");
+    eprintln!("    intersection, the union or the difference of their languages, and then");
+    eprintln!("    prints it to the standard output stream in graphviz dot format. Each state");
+    eprintln!("    is labeled by the pair of states of the determinized FSMs, where ⊥ stands");
+    eprintln!("    for the implicit sink state.");
+    eprintln!();
+    eprintln!("  complement <file> [-a|--alphabet <letters>]");
+    eprintln!("    Complements the FSM in <file> by determinizing it and completing it with a");
+    eprintln!("    sink state, and then prints it to the standard output stream in graphviz");
+    eprintln!("    dot format.");
+    eprintln!("    Options:");
+    eprintln!("      -a|--alphabet <letters>  Adds <letters> (for example 'abc') to the");
+    eprintln!("                               alphabet the complement is taken with respect");
+    eprintln!("                               to. By default only the letters appearing in");
+    eprintln!("                               the FSM are used.");
+    eprintln!();
+    eprintln!("  grammar_to_fsm <file>");
+    eprintln!("    Converts the right-linear or left-linear grammar in <file> to a finite state");
+    eprintln!("    machine, and then prints it to the standard output stream in graphviz dot");
+    eprintln!("    format. Left-linear grammars are converted by reversing the language. The");
+    eprintln!("    grammar has the form 'grammar {{ S -> a S | b A; A -> _; }}', where the");
+    eprintln!("    symbols must be separated by spaces and _ is the empty string.");
+    eprintln!();
+    eprintln!("  fsm_to_grammar <file> [-l|--left]");
+    eprintln!("    Converts the FSM in <file> to a right-linear grammar, or to a left-linear");
+    eprintln!("    grammar (by reversing the language) if --left is specified, and then prints");
+    eprintln!("    it to the standard output stream.");
+    eprintln!();
+    eprintln!("  backprop <file> [-c|--clean]");
+    eprintln!("  forwardprop <file> [-c|--clean]");
     eprintln!("    Eliminates spontaneous moves (epsilon-transitions) from the FSM in <file>,");
     eprintln!("    either by backward propagation (backprop) or by forward propagation");
-    eprintln!("    (forwardprop). The result is printed to the standard output in graphviz dot");
-    eprintln!("    format.");
+    eprintln!("    (forwardprop). The original FSM, the FSM after each step of the elimination");
+    eprintln!("    and the result are printed to the standard output in graphviz dot format.");
+    eprintln!("    The transitions and the initial or final states added by each step are");
+    eprintln!("    logged to the standard error stream.");
+    eprintln!("    Options:");
+    eprintln!("      -c|--clean   Removes the states left unreachable after the elimination");
+    eprintln!("                   and renumbers the remaining ones.");
+    eprintln!();
+    eprintln!("Regular expressions:");
+    eprintln!();
+    eprintln!("  Letters are lowercase, _ is the empty string, [r] is an optional r, and the");
+    eprintln!("  operators are, from the highest to the lowest precedence:");
+    eprintln!("    a-z          range of letters, same as a|b|...|z");
+    eprintln!("    r* r+        iterations");
+    eprintln!("    r{{n}} r{{n,}} r{{n,m}}");
+    eprintln!("                 repetition, from n to m times (or at least n times)");
+    eprintln!("    ~r ¬r        complement");
+    eprintln!("    r s r.s      concatenation");
+    eprintln!("    r&s r∩s      intersection");
+    eprintln!("    r|s          union");
+    eprintln!("  Complements are taken with respect to the letters occurring in the whole");
+    eprintln!("  expression, and in the other operand when two languages are compared or");
+    eprintln!("  divided. Complements and intersections are built on the Berry-Sethi");
+    eprintln!("  machines of their operands, and are not supported by the derivatives");
+    eprintln!("  command.");
     eprintln!();
 }
 
@@ -115,8 +340,17 @@ fn parse_list_arg(arg: &str) -> Option<Vec<i32>> {
     Some(res)
 }
 
+fn parse_num_arg(arg: &str) -> Option<usize> {
+    if let Ok(v) = arg.trim().parse::<usize>() {
+        Some(v)
+    } else {
+        eprintln!("error: cannot parse number \"{arg}\" in arguments");
+        None
+    }
+}
+
 fn cmd_echo_mnet(args: &[String]) -> Result<&[String], CmdError> {
-    if args.len() < 1 {
+    if args.is_empty() {
         eprintln!("error: missing argument to \"echo_mnet\" command");
         return Err(CmdError::BadArgs);
     }
@@ -132,7 +366,7 @@ fn cmd_echo_mnet(args: &[String]) -> Result<&[String], CmdError> {
 }
 
 fn cmd_pilot(args: &[String]) -> Result<&[String], CmdError> {
-    if args.len() < 1 {
+    if args.is_empty() {
         eprintln!("error: missing argument to \"pilot\" command");
         return Err(CmdError::BadArgs);
     }
@@ -150,7 +384,7 @@ fn cmd_pilot(args: &[String]) -> Result<&[String], CmdError> {
 }
 
 fn cmd_echo_regex(args: &[String]) -> Result<&[String], CmdError> {
-    if args.len() < 1 {
+    if args.is_empty() {
         eprintln!("error: missing argument to \"echo_regex\" command");
         return Err(CmdError::BadArgs);
     }
@@ -165,7 +399,7 @@ fn cmd_echo_regex(args: &[String]) -> Result<&[String], CmdError> {
 }
 
 fn cmd_echo_fsm(args: &[String]) -> Result<&[String], CmdError> {
-    if args.len() < 1 {
+    if args.is_empty() {
         eprintln!("error: missing argument to \"echo_fsm\" command");
         return Err(CmdError::BadArgs);
     }
@@ -181,39 +415,144 @@ fn cmd_echo_fsm(args: &[String]) -> Result<&[String], CmdError> {
 }
 
 fn cmd_berry_sethi(args: &[String]) -> Result<&[String], CmdError> {
-    if args.len() < 1 {
+    if args.is_empty() {
         eprintln!("error: missing argument to \"berry_sethi\" command");
         return Err(CmdError::BadArgs);
     }
     let re_str = &args[0];
-    let args_left = &args[1..];
+    let mut args_left = &args[1..];
+    let mut minimize = false;
+    let mut snf = false;
+    loop {
+        if !args_left.is_empty() && (args_left[0] == "--minimize" || args_left[0] == "-m") {
+            minimize = true;
+            args_left = &args_left[1..];
+        } else if !args_left.is_empty() && (args_left[0] == "--snf" || args_left[0] == "-s") {
+            snf = true;
+            args_left = &args_left[1..];
+        } else {
+            break;
+        }
+    }
     let mut pars = RegexParser::new(re_str);
-    let Some(re) = pars.parse_regex() else {
+    let Some(mut re) = pars.parse_regex() else {
         return Err(CmdError::ExecError);
     };
-
-    if args_left.len() >= 1 && (args_left[0] == "--minimize" || args_left[0] == "-m") {
-        eprintln!("{}", re.to_string_numbered());
+    if re.is_extended() && snf {
+        eprintln!("error: complement and intersection have no star normal form");
+        return Err(CmdError::ExecError);
+    }
+    eprintln!("{}", re.to_string_numbered());
+    if snf {
+        re = re.to_star_normal_form();
+        eprintln!("Star normal form: {}", re.to_string_numbered());
+    }
+    // The operands of complements and intersections are converted separately,
+    // so their positions have no local sets in the whole expression
+    if !re.is_extended() {
         re.dump_local_sets();
-        
-        let fsm = Machine::from_machine(berry_sethi(&re));
+    }
+    if minimize {
+        // Complements may introduce nondeterminism through the substitution
+        let fsm = if re.is_extended() {
+            regex_to_machine(&re).determinized()
+        } else {
+            Machine::from_machine(berry_sethi(&re))
+        };
         let mut dist = fsm.dist_table_len_0();
         while fsm.dist_table_update(&mut dist) > 0 { }
         let sets = fsm.cliques(&dist);
         let m = MinimizedMachine::from_machine_and_equiv_sets(&fsm, &sets);
         println!("{}", m.to_dot(false));
-
-        Ok(&args[2..])
+    } else if re.is_extended() {
+        println!("{}", regex_to_machine(&re).to_dot(false));
     } else {
-        eprintln!("{}", re.to_string_numbered());
-        re.dump_local_sets();
         println!("{}", berry_sethi(&re).to_dot(false));
+    }
+    Ok(args_left)
+}
+
+fn cmd_glushkov(args: &[String]) -> Result<&[String], CmdError> {
+    if args.is_empty() {
+        eprintln!("error: missing argument to \"glushkov\" command");
+        return Err(CmdError::BadArgs);
+    }
+    let mut pars = RegexParser::new(&args[0]);
+    let Some(re) = pars.parse_regex() else {
+        return Err(CmdError::ExecError);
+    };
+    if re.is_extended() {
+        eprintln!("error: complement and intersection are not supported by the Glushkov construction");
+        return Err(CmdError::ExecError);
+    }
+    eprintln!("{}", re.to_string_numbered());
+    re.dump_local_sets();
+    println!("{}", glushkov(&re).to_dot(false));
+    Ok(&args[1..])
+}
+
+fn cmd_snf(args: &[String]) -> Result<&[String], CmdError> {
+    if args.is_empty() {
+        eprintln!("error: missing argument to \"snf\" command");
+        return Err(CmdError::BadArgs);
+    }
+    let mut pars = RegexParser::new(&args[0]);
+    let Some(re) = pars.parse_regex() else {
+        return Err(CmdError::ExecError);
+    };
+    if re.is_extended() {
+        eprintln!("error: star normal form is not defined for complement and intersection");
+        return Err(CmdError::ExecError);
+    }
+    let snf_re = re.to_star_normal_form();
+    eprintln!("{} in star normal form is {}", re.to_string_numbered(), snf_re.to_string_numbered());
+    println!("{snf_re}");
+    Ok(&args[1..])
+}
+
+fn cmd_thompson(args: &[String]) -> Result<&[String], CmdError> {
+    if args.is_empty() {
+        eprintln!("error: missing argument to \"thompson\" command");
+        return Err(CmdError::BadArgs);
+    }
+    let re_str = &args[0];
+    let mut pars = RegexParser::new(re_str);
+    if let Some(re) = pars.parse_regex() {
+        println!("{}", thompson(&re).to_dot(false));
+        Ok(&args[1..])
+    } else {
+        Err(CmdError::ExecError)
+    }
+}
+
+fn cmd_derivatives(args: &[String]) -> Result<&[String], CmdError> {
+    if args.is_empty() {
+        eprintln!("error: missing argument to \"derivatives\" command");
+        return Err(CmdError::BadArgs);
+    }
+    let re_str = &args[0];
+    let mut pars = RegexParser::new(re_str);
+    if let Some(re) = pars.parse_regex() {
+        if re.is_extended() {
+            eprintln!("error: complement and intersection are not supported by the derivatives construction");
+            return Err(CmdError::ExecError);
+        }
+        let dfa = derivatives_dfa(&re);
+        for s in &dfa.states {
+            eprintln!("{} = {}", s.id, s.label);
+            for t in &s.transitions {
+                eprintln!("  ∂{}({}) = {}", t.label, s.label, dfa.lookup_state(t.dest_id).label);
+            }
+        }
+        println!("{}", dfa.to_dot(false));
         Ok(&args[1..])
+    } else {
+        Err(CmdError::ExecError)
     }
 }
 
 fn cmd_berry_sethi_fsm(args: &[String]) -> Result<&[String], CmdError> {
-    if args.len() < 1 {
+    if args.is_empty() {
         eprintln!("error: missing argument to \"berry_sethi_fsm\" command");
         return Err(CmdError::BadArgs);
     }
@@ -233,50 +572,52 @@ fn cmd_berry_sethi_fsm(args: &[String]) -> Result<&[String], CmdError> {
     }
 }
 
-fn bmc_find_shortest(fsm: &Machine) -> Vec<i32> {
-    let mut best: Vec<i32> = vec![];
-    let mut best_len = 0;
-    let states = fsm.states_ids();
-    let len_states = states.len();
-    for list in states.into_iter().permutations(len_states) {
-        let mut bmc_fsm = BMCMachine::from_machine(fsm);
-        bmc_fsm.merge_parallel_transitions();
-        for sid in &list {
-            bmc_fsm.eliminate(*sid);
-            bmc_fsm.merge_parallel_transitions();
-        }
-        let re = bmc_fsm.regex().to_string();
-        if best_len == 0 || re.len() < best_len {
-            best_len = re.len();
-            best = list;
-            eprintln!("{:?} -> {}", best, re);
-        }
-    }
-    best
-}
-
 fn cmd_bmc(args: &[String]) -> Result<&[String], CmdError> {
-    if args.len() < 1 {
+    if args.is_empty() {
         eprintln!("error: missing argument to \"bmc\" command");
         return Err(CmdError::BadArgs);
     }
     let file = &args[0];
-    let args_left = &args[1..];
     let lex = Lexer::from_path(Path::new(file));
     let mut pars = Parser::new(lex);
     let Some(fsm) = validated(pars.parse_machine_file()) else {
         return Err(CmdError::ExecError);
     };
 
-    if args_left.len() >= 1 && (args_left[0] == "--order" || args_left[0] == "-o") {
-        if args_left.len() < 2 {
-            eprintln!("error: missing argument to \"--order\"");
-            return Err(CmdError::BadArgs);
+    let mut args_left = &args[1..];
+    let mut order: Option<&str> = None;
+    let mut metric = RegexMetric::Length;
+    loop {
+        if !args_left.is_empty() && (args_left[0] == "--order" || args_left[0] == "-o") {
+            if args_left.len() < 2 {
+                eprintln!("error: missing argument to \"--order\"");
+                return Err(CmdError::BadArgs);
+            }
+            order = Some(args_left[1].as_str());
+            args_left = &args_left[2..];
+        } else if !args_left.is_empty() && (args_left[0] == "--metric" || args_left[0] == "-m") {
+            if args_left.len() < 2 {
+                eprintln!("error: missing argument to \"--metric\"");
+                return Err(CmdError::BadArgs);
+            }
+            let Some(m) = RegexMetric::from_name(&args_left[1]) else {
+                eprintln!("error: unknown metric \"{}\"", args_left[1]);
+                return Err(CmdError::BadArgs);
+            };
+            metric = m;
+            args_left = &args_left[2..];
+        } else {
+            break;
         }
-        let list = if args_left[1] == "best" {
-            bmc_find_shortest(&fsm)
+    }
+
+    if let Some(order) = order {
+        let list = if order == "best" {
+            let (list, cost) = bmc_find_best(&fsm, metric);
+            eprintln!("Best order found: {} ({metric} {cost})", list.iter().join(","));
+            list
         } else {
-            let Some(list) = parse_list_arg(args_left[1].as_str()) else {
+            let Some(list) = parse_list_arg(order) else {
                 return Err(CmdError::BadArgs);
             };
             list
@@ -296,7 +637,7 @@ fn cmd_bmc(args: &[String]) -> Result<&[String], CmdError> {
             println!("{}", bmc_fsm.to_dot_2(false, false));
         }
         println!("}}");
-        Ok(&args_left[2..])
+        Ok(args_left)
     } else {
         let mut bmc_fsm = BMCMachine::from_machine(&fsm);
         println!("digraph {{\n  rankdir=\"LR\";");
@@ -313,64 +654,652 @@ fn cmd_bmc(args: &[String]) -> Result<&[String], CmdError> {
     }
 }
 
-fn cmd_backprop(args: &[String]) -> Result<&[String], CmdError> {
-    if args.len() < 1 {
-        eprintln!("error: missing argument to \"backprop\" command");
+fn cmd_epsilon_elim<'a>(args: &'a [String], cmd: &str, forward: bool) -> Result<&'a [String], CmdError> {
+    if args.is_empty() {
+        eprintln!("error: missing argument to \"{cmd}\" command");
+        return Err(CmdError::BadArgs);
+    }
+    let Some(mut fsm) = load_machine(&args[0]) else {
+        return Err(CmdError::ExecError);
+    };
+    let args_left = &args[1..];
+    let clean = !args_left.is_empty() && (args_left[0] == "--clean" || args_left[0] == "-c");
+    let mut label = GenMachineLabel{ name: fsm.label, gen_id: 1 };
+    println!("digraph {{\n  rankdir=\"LR\";");
+    println!("{}", fsm.with_label(label).to_dot_2(false, false));
+    eprintln!("Closing spontaneous moves by transitivity");
+    for step in fsm.epsilon_trans_closure() {
+        eprintln!("{step}");
+    }
+    label.gen_id += 1;
+    println!("{}", fsm.with_label(label).to_dot_2(false, false));
+    if forward {
+        eprintln!("Propagating transitions forward");
+        for step in fsm.forward_propagation() {
+            eprintln!("{step}");
+        }
+    } else {
+        eprintln!("Propagating transitions backward");
+        for step in fsm.backward_propagation() {
+            eprintln!("{step}");
+        }
+    }
+    label.gen_id += 1;
+    println!("{}", fsm.with_label(label).to_dot_2(false, false));
+    eprintln!("Removing spontaneous moves");
+    for step in fsm.remove_epsilon_trans() {
+        eprintln!("{step}");
+    }
+    label.gen_id += 1;
+    println!("{}", fsm.with_label(label).to_dot_2(false, false));
+    if clean {
+        eprintln!("Removing unreachable states");
+        let reachable = fsm.reachable_states_ids();
+        let unreachable: Vec<_> = fsm.states_ids().into_iter().filter(|sid| !reachable.contains(sid)).collect();
+        for sid in &unreachable {
+            eprintln!("Removing state {sid}");
+        }
+        fsm.remove_states(&unreachable);
+        for (old_id, new_id) in fsm.renumber() {
+            eprintln!("Renumbering state {old_id} to {new_id}");
+        }
+        label.gen_id += 1;
+        println!("{}", fsm.with_label(label).to_dot_2(false, false));
+    }
+    println!("}}");
+    Ok(if clean { &args_left[1..] } else { args_left })
+}
+
+fn cmd_trim(args: &[String]) -> Result<&[String], CmdError> {
+    if args.is_empty() {
+        eprintln!("error: missing argument to \"trim\" command");
+        return Err(CmdError::BadArgs);
+    }
+    let Some(mut fsm) = load_machine(&args[0]) else {
+        return Err(CmdError::ExecError);
+    };
+    for sid in fsm.trim() {
+        eprintln!("Removing state {sid}");
+    }
+    println!("{}", fsm.to_dot(false));
+    Ok(&args[1..])
+}
+
+fn cmd_minimize(args: &[String]) -> Result<&[String], CmdError> {
+    if args.is_empty() {
+        eprintln!("error: missing argument to \"minimize\" command");
         return Err(CmdError::BadArgs);
     }
     let file = &args[0];
-    let lex = Lexer::from_path(Path::new(file));
-    let mut pars = Parser::new(lex);
-    if let Some(mut fsm) = validated(pars.parse_machine_file()) {
+    let mut args_left = &args[1..];
+    let mut algo = "nerode";
+    let mut determinize = false;
+    loop {
+        if !args_left.is_empty() && (args_left[0] == "--algo" || args_left[0] == "-a") {
+            if args_left.len() < 2 {
+                eprintln!("error: missing argument to \"--algo\"");
+                return Err(CmdError::BadArgs);
+            }
+            algo = args_left[1].as_str();
+            if algo != "nerode" && algo != "hopcroft" && algo != "brzozowski" {
+                eprintln!("error: unknown minimization algorithm \"{algo}\"");
+                return Err(CmdError::BadArgs);
+            }
+            args_left = &args_left[2..];
+        } else if !args_left.is_empty() && (args_left[0] == "--determinize" || args_left[0] == "-d") {
+            determinize = true;
+            args_left = &args_left[1..];
+        } else {
+            break;
+        }
+    }
+    let Some(mut fsm) = load_machine(file) else {
+        return Err(CmdError::ExecError);
+    };
+    if fsm.shortest_word().is_none() {
+        // Trimming would remove every state, including the initial one
+        eprintln!("warning: the language of machine {} is empty", fsm.label);
+        println!("{}", Machine::empty_language(fsm.label).to_dot(false));
+        return Ok(args_left);
+    }
+    if algo == "brzozowski" {
+        // Determinizing the reversal twice yields the minimal machine
+        eprintln!("Determinizing the reversal of machine {}", fsm.label);
+        let dfa = subset_construction(&fsm.reverse());
+        dfa.dump_subsets();
+        let rev_dfa = Machine::from_machine(dfa);
+        eprintln!("Determinizing the reversal of the result");
+        let dfa = subset_construction(&rev_dfa.reverse());
+        dfa.dump_subsets();
+        let mut m = Machine::from_machine(dfa);
+        m.trim();
+        println!("{}", m.to_dot(false));
+        return Ok(args_left);
+    }
+    if !fsm.is_deterministic() {
+        if !determinize {
+            fsm.validate_deterministic();
+            eprintln!("error: machine {} is not deterministic (use --determinize)", fsm.label);
+            return Err(CmdError::ExecError);
+        }
+        eprintln!("Machine {} is not deterministic, determinizing it first", fsm.label);
         fsm.epsilon_trans_closure();
         fsm.backward_propagation();
         fsm.remove_epsilon_trans();
-        println!("{}", fsm.to_dot(false));
+        let dfa = subset_construction(&fsm);
+        dfa.dump_subsets();
+        fsm = Machine::from_machine(dfa);
+    }
+    fsm.trim();
+    let sets = if algo == "hopcroft" {
+        fsm.hopcroft_equiv_sets()
+    } else {
+        let mut dist = fsm.dist_table_len_0();
+        while fsm.dist_table_update(&mut dist) > 0 { }
+        fsm.cliques(&dist)
+    };
+    let m = MinimizedMachine::from_machine_and_equiv_sets(&fsm, &sets);
+    println!("{}", m.to_dot(false));
+    Ok(args_left)
+}
+
+fn cmd_reverse(args: &[String]) -> Result<&[String], CmdError> {
+    if args.is_empty() {
+        eprintln!("error: missing argument to \"reverse\" command");
+        return Err(CmdError::BadArgs);
+    }
+    if let Some(fsm) = load_machine(&args[0]) {
+        println!("{}", fsm.reverse().to_dot(false));
         Ok(&args[1..])
     } else {
         Err(CmdError::ExecError)
     }
 }
 
-fn cmd_forwardprop(args: &[String]) -> Result<&[String], CmdError> {
-    if args.len() < 1 {
-        eprintln!("error: missing argument to \"forwardprop\" command");
+fn cmd_reverse_regex(args: &[String]) -> Result<&[String], CmdError> {
+    if args.is_empty() {
+        eprintln!("error: missing argument to \"reverse_regex\" command");
+        return Err(CmdError::BadArgs);
+    }
+    let mut pars = RegexParser::new(&args[0]);
+    if let Some(re) = pars.parse_regex() {
+        eprintln!("{} reversed is {}", re.to_string_numbered(), re.reverse().to_string_numbered());
+        println!("{}", re.reverse());
+        Ok(&args[1..])
+    } else {
+        Err(CmdError::ExecError)
+    }
+}
+
+fn cmd_quotient<'a>(args: &'a [String], cmd: &str) -> Result<&'a [String], CmdError> {
+    if args.len() < 2 {
+        eprintln!("error: missing argument to \"{cmd}\" command");
+        return Err(CmdError::BadArgs);
+    }
+    let Some(fsm) = load_machine(&args[0]) else {
+        return Err(CmdError::ExecError);
+    };
+    let Some(re) = RegexParser::new(&args[1]).parse_regex() else {
+        return Err(CmdError::ExecError);
+    };
+    let divisor = regex_to_machine_over(&re, &merge_alphabets(&re.alphabet(), &fsm.alphabet()));
+    let res = if cmd == "left_quotient" {
+        fsm.left_quotient(&divisor)
+    } else {
+        fsm.right_quotient(&divisor)
+    };
+    println!("{}", res.to_dot(false));
+    Ok(&args[2..])
+}
+
+fn cmd_closure<'a>(args: &'a [String], cmd: &str, kind: Closure) -> Result<&'a [String], CmdError> {
+    if args.is_empty() {
+        eprintln!("error: missing argument to \"{cmd}\" command");
+        return Err(CmdError::BadArgs);
+    }
+    let Some(fsm) = load_machine(&args[0]) else {
+        return Err(CmdError::ExecError);
+    };
+    println!("{}", fsm.closure(kind).to_dot(false));
+    Ok(&args[1..])
+}
+
+fn cmd_substitute(args: &[String]) -> Result<&[String], CmdError> {
+    if args.is_empty() {
+        eprintln!("error: missing argument to \"substitute\" command");
         return Err(CmdError::BadArgs);
     }
     let file = &args[0];
+    let mut args_left = &args[1..];
+    let mut images: HashMap<char, Machine> = HashMap::new();
+    while !args_left.is_empty() && (args_left[0] == "--subst" || args_left[0] == "-s") {
+        if args_left.len() < 2 {
+            eprintln!("error: missing argument to \"--subst\"");
+            return Err(CmdError::BadArgs);
+        }
+        let mut chars = args_left[1].chars();
+        let (Some(c), Some('='), re_str) = (chars.next(), chars.next(), chars.as_str()) else {
+            eprintln!("error: \"{}\" is not a substitution of the form <letter>=<regex>", args_left[1]);
+            return Err(CmdError::BadArgs);
+        };
+        if c == '_' {
+            eprintln!("error: the empty string cannot be substituted");
+            return Err(CmdError::BadArgs);
+        }
+        let Some(re) = RegexParser::new(re_str).parse_regex() else {
+            return Err(CmdError::ExecError);
+        };
+        if images.insert(c, regex_to_machine(&re)).is_some() {
+            eprintln!("warning: multiple substitutions for letter {c}, using the last one");
+        }
+        args_left = &args_left[2..];
+    }
+    let Some(fsm) = load_machine(file) else {
+        return Err(CmdError::ExecError);
+    };
+    println!("{}", fsm.substitute(&images).to_dot(false));
+    Ok(args_left)
+}
+
+fn cmd_regex_info(args: &[String]) -> Result<&[String], CmdError> {
+    if args.is_empty() {
+        eprintln!("error: missing argument to \"regex_info\" command");
+        return Err(CmdError::BadArgs);
+    }
+    let mut pars = RegexParser::new(&args[0]);
+    let Some(re) = pars.parse_regex() else {
+        return Err(CmdError::ExecError);
+    };
+    let yes_no = |b: bool| if b { "yes" } else { "no" };
+    println!("size: {}", re.size());
+    println!("star height: {}", re.star_height());
+    println!("operators: {}", re.operator_count());
+    println!("literals (alphabetic width): {}", re.literal_count());
+    println!("alphabet: {{{}}}", re.alphabet().iter().join(", "));
+    if re.is_extended() {
+        println!("star normal form: not defined for complement and intersection");
+    } else {
+        println!("star normal form: {}", yes_no(re.is_star_normal_form()));
+    }
+    Ok(&args[1..])
+}
+
+fn cmd_ambiguous_regex(args: &[String]) -> Result<&[String], CmdError> {
+    if args.is_empty() {
+        eprintln!("error: missing argument to \"ambiguous_regex\" command");
+        return Err(CmdError::BadArgs);
+    }
+    let mut pars = RegexParser::new(&args[0]);
+    let Some(re) = pars.parse_regex() else {
+        return Err(CmdError::ExecError);
+    };
+    if re.is_extended() {
+        eprintln!("error: ambiguity of complement and intersection is not defined");
+        return Err(CmdError::ExecError);
+    }
+    eprintln!("{}", re.to_string_numbered());
+    if let Some((left, right)) = re.ambiguous_word() {
+        let word: String = left.iter().map(|t| t.c).collect();
+        println!("ambiguous: {} has numberings {} and {}", format_word(&word), left.iter().join(" "), right.iter().join(" "));
+    } else {
+        println!("not ambiguous");
+    }
+    Ok(&args[1..])
+}
+
+fn cmd_simplify_regex(args: &[String]) -> Result<&[String], CmdError> {
+    if args.is_empty() {
+        eprintln!("error: missing argument to \"simplify_regex\" command");
+        return Err(CmdError::BadArgs);
+    }
+    let mut pars = RegexParser::new(&args[0]);
+    if let Some(re) = pars.parse_regex() {
+        println!("{}", re.simplify());
+        Ok(&args[1..])
+    } else {
+        Err(CmdError::ExecError)
+    }
+}
+
+fn cmd_determinize(args: &[String]) -> Result<&[String], CmdError> {
+    if args.is_empty() {
+        eprintln!("error: missing argument to \"determinize\" command");
+        return Err(CmdError::BadArgs);
+    }
+    let Some(fsm) = load_machine(&args[0]) else {
+        return Err(CmdError::ExecError);
+    };
+    let dfa = subset_construction(&fsm);
+    dfa.dump_subsets();
+    println!("{}", dfa.to_dot(false));
+    Ok(&args[1..])
+}
+
+fn load_machine(file: &str) -> Option<Machine> {
     let lex = Lexer::from_path(Path::new(file));
     let mut pars = Parser::new(lex);
-    if let Some(mut fsm) = validated(pars.parse_machine_file()) {
-        fsm.epsilon_trans_closure();
-        fsm.forward_propagation();
-        fsm.remove_epsilon_trans();
-        println!("{}", fsm.to_dot(false));
-        Ok(&args[1..])
+    validated(pars.parse_machine_file())
+}
+
+fn print_equiv(fsm_1: &Machine, name_1: &str, fsm_2: &Machine, name_2: &str) {
+    match distinguishing_word(fsm_1, fsm_2) {
+        None => println!("equivalent"),
+        Some((word, true)) => println!("not equivalent: {} is accepted by {name_1} and not by {name_2}", format_word(&word)),
+        Some((word, false)) => println!("not equivalent: {} is accepted by {name_2} and not by {name_1}", format_word(&word))
+    }
+}
+
+fn cmd_equiv(args: &[String]) -> Result<&[String], CmdError> {
+    if args.len() < 2 {
+        eprintln!("error: missing argument to \"equiv\" command");
+        return Err(CmdError::BadArgs);
+    }
+    let (file_1, file_2) = (&args[0], &args[1]);
+    let (Some(fsm_1), Some(fsm_2)) = (load_machine(file_1), load_machine(file_2)) else {
+        return Err(CmdError::ExecError);
+    };
+    print_equiv(&fsm_1, file_1, &fsm_2, file_2);
+    Ok(&args[2..])
+}
+
+fn cmd_equiv_regex(args: &[String]) -> Result<&[String], CmdError> {
+    if args.len() < 2 {
+        eprintln!("error: missing argument to \"equiv_regex\" command");
+        return Err(CmdError::BadArgs);
+    }
+    let Some(re_1) = RegexParser::new(&args[0]).parse_regex() else {
+        return Err(CmdError::ExecError);
+    };
+    let Some(re_2) = RegexParser::new(&args[1]).parse_regex() else {
+        return Err(CmdError::ExecError);
+    };
+    let alphabet = merge_alphabets(&re_1.alphabet(), &re_2.alphabet());
+    let fsm_1 = regex_to_machine_over(&re_1, &alphabet);
+    let fsm_2 = regex_to_machine_over(&re_2, &alphabet);
+    print_equiv(&fsm_1, &re_1.to_string(), &fsm_2, &re_2.to_string());
+    Ok(&args[2..])
+}
+
+fn cmd_equiv_regex_fsm(args: &[String]) -> Result<&[String], CmdError> {
+    if args.len() < 2 {
+        eprintln!("error: missing argument to \"equiv_regex_fsm\" command");
+        return Err(CmdError::BadArgs);
+    }
+    let Some(re) = RegexParser::new(&args[0]).parse_regex() else {
+        return Err(CmdError::ExecError);
+    };
+    let file = &args[1];
+    let Some(fsm) = load_machine(file) else {
+        return Err(CmdError::ExecError);
+    };
+    let re_fsm = regex_to_machine_over(&re, &merge_alphabets(&re.alphabet(), &fsm.alphabet()));
+    print_equiv(&re_fsm, &re.to_string(), &fsm, file);
+    Ok(&args[2..])
+}
+
+fn cmd_bool_op<'a>(args: &'a [String], cmd: &str, op: BoolOp) -> Result<&'a [String], CmdError> {
+    if args.len() < 2 {
+        eprintln!("error: missing argument to \"{cmd}\" command");
+        return Err(CmdError::BadArgs);
+    }
+    let (Some(fsm_1), Some(fsm_2)) = (load_machine(&args[0]), load_machine(&args[1])) else {
+        return Err(CmdError::ExecError);
+    };
+    println!("{}", fsm_1.product(&fsm_2, op).to_dot(false));
+    Ok(&args[2..])
+}
+
+fn print_run(fsm: &Machine, name: &str, word: &str) {
+    let run = fsm.run(word);
+    let mut steps = vec![format!("{{{}}}", run[0].iter().join(", "))];
+    for (c, active) in word.chars().zip(&run[1..]) {
+        steps.push(format!("-{c}-> {{{}}}", active.iter().join(", ")));
+    }
+    let outcome = if fsm.accepts(word) { "accepts" } else { "rejects" };
+    println!("{name} {outcome}: {}", steps.join(" "));
+}
+
+fn cmd_accepts(args: &[String]) -> Result<&[String], CmdError> {
+    if args.len() < 2 {
+        eprintln!("error: missing argument to \"accepts\" command");
+        return Err(CmdError::BadArgs);
+    }
+    let Some(fsm) = load_machine(&args[0]) else {
+        return Err(CmdError::ExecError);
+    };
+    let word = args[1].replace('_', "");
+    let args_left = &args[2..];
+    let all_runs = !args_left.is_empty() && args_left[0] == "--all-runs";
+
+    let run = fsm.run(&word);
+    for (i, active) in run.iter().enumerate() {
+        let prefix: String = word.chars().take(i).collect();
+        println!("{}: {{{}}}", format_word(&prefix), active.iter().join(", "));
+    }
+    if all_runs {
+        for (ini, path) in fsm.accepting_runs(&word) {
+            let steps = path.iter().map(|t| {
+                let label = if t.is_epsilon() { 'ε' } else { t.label };
+                format!(" -{label}-> {}", t.dest_id)
+            }).join("");
+            println!("accepting run: {ini}{steps}");
+        }
+    }
+    if fsm.accepts(&word) {
+        println!("accepted");
+        Ok(if all_runs { &args_left[1..] } else { args_left })
     } else {
+        println!("rejected");
         Err(CmdError::ExecError)
     }
 }
 
-fn cmd_minimize(args: &[String]) -> Result<&[String], CmdError> {
-    if args.len() < 1 {
-        eprintln!("error: missing argument to \"minimize\" command");
+fn parse_words_options(mut args: &[String]) -> Result<(usize, usize, &[String]), CmdError> {
+    let mut max_len = 10;
+    let mut limit = 20;
+    while !args.is_empty() && (args[0] == "--max-len" || args[0] == "--limit") {
+        if args.len() < 2 {
+            eprintln!("error: missing argument to \"{}\"", args[0]);
+            return Err(CmdError::BadArgs);
+        }
+        let Some(v) = parse_num_arg(&args[1]) else {
+            return Err(CmdError::BadArgs);
+        };
+        if args[0] == "--max-len" {
+            max_len = v;
+        } else {
+            limit = v;
+        }
+        args = &args[2..];
+    }
+    Ok((max_len, limit, args))
+}
+
+fn print_words(fsm: &Machine, max_len: usize, limit: usize) {
+    for word in fsm.words(max_len, limit) {
+        println!("{}", format_word(&word));
+    }
+}
+
+fn cmd_words(args: &[String]) -> Result<&[String], CmdError> {
+    if args.is_empty() {
+        eprintln!("error: missing argument to \"words\" command");
+        return Err(CmdError::BadArgs);
+    }
+    let Some(fsm) = load_machine(&args[0]) else {
+        return Err(CmdError::ExecError);
+    };
+    let (max_len, limit, args_left) = parse_words_options(&args[1..])?;
+    print_words(&fsm, max_len, limit);
+    Ok(args_left)
+}
+
+fn cmd_words_regex(args: &[String]) -> Result<&[String], CmdError> {
+    if args.is_empty() {
+        eprintln!("error: missing argument to \"words_regex\" command");
+        return Err(CmdError::BadArgs);
+    }
+    let Some(re) = RegexParser::new(&args[0]).parse_regex() else {
+        return Err(CmdError::ExecError);
+    };
+    let (max_len, limit, args_left) = parse_words_options(&args[1..])?;
+    print_words(&regex_to_machine(&re), max_len, limit);
+    Ok(args_left)
+}
+
+fn print_lang_info<'a>(fsm: &Machine, args: &'a [String]) -> Result<&'a [String], CmdError> {
+    let mut max_len = 10;
+    let mut args_left = args;
+    if !args_left.is_empty() && args_left[0] == "--max-len" {
+        if args_left.len() < 2 {
+            eprintln!("error: missing argument to \"--max-len\"");
+            return Err(CmdError::BadArgs);
+        }
+        let Some(v) = parse_num_arg(&args_left[1]) else {
+            return Err(CmdError::BadArgs);
+        };
+        max_len = v;
+        args_left = &args_left[2..];
+    }
+    let info = fsm.lang_info(max_len);
+    let yes_no = |b: bool| if b { "yes" } else { "no" };
+    println!("empty: {}", yes_no(info.is_empty));
+    println!("finite: {}", yes_no(info.is_finite));
+    if let Some(n) = info.cardinality {
+        println!("cardinality: {n}");
+    } else {
+        println!("cardinality: infinite");
+    }
+    if let Some(word) = info.shortest_word {
+        println!("shortest word: {}", format_word(&word));
+    }
+    if let Some(word) = info.longest_word {
+        println!("longest word: {}", format_word(&word));
+    }
+    for (len, n) in info.counts_by_len.iter().enumerate() {
+        println!("words of length {len}: {n}");
+    }
+    Ok(args_left)
+}
+
+fn cmd_lang_info(args: &[String]) -> Result<&[String], CmdError> {
+    if args.is_empty() {
+        eprintln!("error: missing argument to \"lang_info\" command");
+        return Err(CmdError::BadArgs);
+    }
+    let Some(fsm) = load_machine(&args[0]) else {
+        return Err(CmdError::ExecError);
+    };
+    print_lang_info(&fsm, &args[1..])
+}
+
+fn cmd_lang_info_regex(args: &[String]) -> Result<&[String], CmdError> {
+    if args.is_empty() {
+        eprintln!("error: missing argument to \"lang_info_regex\" command");
+        return Err(CmdError::BadArgs);
+    }
+    let Some(re) = RegexParser::new(&args[0]).parse_regex() else {
+        return Err(CmdError::ExecError);
+    };
+    print_lang_info(&regex_to_machine(&re), &args[1..])
+}
+
+fn cmd_grammar_to_fsm(args: &[String]) -> Result<&[String], CmdError> {
+    if args.is_empty() {
+        eprintln!("error: missing argument to \"grammar_to_fsm\" command");
         return Err(CmdError::BadArgs);
     }
     let file = &args[0];
     let lex = Lexer::from_path(Path::new(file));
     let mut pars = Parser::new(lex);
-    if let Some(fsm) = validated(pars.parse_machine_file()) {
-        let mut dist = fsm.dist_table_len_0();
-        while fsm.dist_table_update(&mut dist) > 0 { }
-        let sets = fsm.cliques(&dist);
-        let m = MinimizedMachine::from_machine_and_equiv_sets(&fsm, &sets);
-        println!("{}", m.to_dot(false));
+    if let Some(grammar) = validated(pars.parse_grammar_file()) {
+        if grammar.linearity() == Some(Linearity::Left) {
+            eprintln!("Converting the reversed right-linear grammar:");
+            eprintln!("{}", grammar.reversed());
+        }
+        for (i, n) in grammar.nonterminals().iter().enumerate() {
+            eprintln!("{n} = state {i}");
+        }
+        println!("{}", grammar.to_machine().to_dot(false));
         Ok(&args[1..])
     } else {
         Err(CmdError::ExecError)
     }
 }
 
+fn cmd_fsm_to_grammar(args: &[String]) -> Result<&[String], CmdError> {
+    if args.is_empty() {
+        eprintln!("error: missing argument to \"fsm_to_grammar\" command");
+        return Err(CmdError::BadArgs);
+    }
+    let Some(fsm) = load_machine(&args[0]) else {
+        return Err(CmdError::ExecError);
+    };
+    let args_left = &args[1..];
+    let (linearity, args_left) = if !args_left.is_empty() && (args_left[0] == "--left" || args_left[0] == "-l") {
+        (Linearity::Left, &args_left[1..])
+    } else {
+        (Linearity::Right, args_left)
+    };
+    if let Some(grammar) = Grammar::from_machine(&fsm, linearity) {
+        println!("{grammar}");
+        Ok(args_left)
+    } else {
+        eprintln!("error: machine {} has too many states", fsm.label);
+        Err(CmdError::ExecError)
+    }
+}
+
+fn cmd_includes(args: &[String]) -> Result<&[String], CmdError> {
+    if args.len() < 2 {
+        eprintln!("error: missing argument to \"includes\" command");
+        return Err(CmdError::BadArgs);
+    }
+    let (file_1, file_2) = (&args[0], &args[1]);
+    let (Some(fsm_1), Some(fsm_2)) = (load_machine(file_1), load_machine(file_2)) else {
+        return Err(CmdError::ExecError);
+    };
+    if let Some(word) = fsm_1.product(&fsm_2, BoolOp::Difference).shortest_word() {
+        println!("not included: {} is accepted by {file_1} and not by {file_2}", format_word(&word));
+        print_run(&fsm_1, file_1, &word);
+        print_run(&fsm_2, file_2, &word);
+    } else {
+        println!("included");
+    }
+    Ok(&args[2..])
+}
+
+fn cmd_complement(args: &[String]) -> Result<&[String], CmdError> {
+    if args.is_empty() {
+        eprintln!("error: missing argument to \"complement\" command");
+        return Err(CmdError::BadArgs);
+    }
+    let Some(fsm) = load_machine(&args[0]) else {
+        return Err(CmdError::ExecError);
+    };
+    let args_left = &args[1..];
+    if !args_left.is_empty() && (args_left[0] == "--alphabet" || args_left[0] == "-a") {
+        if args_left.len() < 2 {
+            eprintln!("error: missing argument to \"--alphabet\"");
+            return Err(CmdError::BadArgs);
+        }
+        let alphabet: Vec<char> = args_left[1].chars().collect();
+        if alphabet.contains(&'_') {
+            eprintln!("error: the alphabet cannot contain the empty string \"_\"");
+            return Err(CmdError::BadArgs);
+        }
+        if let Some(c) = alphabet.iter().duplicates().next() {
+            eprintln!("error: letter \"{c}\" appears more than once in the alphabet");
+            return Err(CmdError::BadArgs);
+        }
+        println!("{}", fsm.complement(&alphabet).to_dot(false));
+        Ok(&args_left[2..])
+    } else {
+        println!("{}", fsm.complement(&[]).to_dot(false));
+        Ok(args_left)
+    }
+}
+
 fn main() -> ExitCode {
     let args: Vec<_> = std::env::args().collect();
     if args.len() == 1 {
@@ -392,16 +1321,78 @@ fn main() -> ExitCode {
             cmd_echo_fsm(&args_left[1..])
         } else if cmd == "berry_sethi" {
             cmd_berry_sethi(&args_left[1..])
+        } else if cmd == "thompson" {
+            cmd_thompson(&args_left[1..])
+        } else if cmd == "derivatives" {
+            cmd_derivatives(&args_left[1..])
         } else if cmd == "berry_sethi_fsm" {
             cmd_berry_sethi_fsm(&args_left[1..])
         } else if cmd == "bmc" {
             cmd_bmc(&args_left[1..])
         } else if cmd == "backprop" {
-            cmd_backprop(&args_left[1..])
+            cmd_epsilon_elim(&args_left[1..], cmd, false)
         } else if cmd == "forwardprop" {
-            cmd_forwardprop(&args_left[1..])
+            cmd_epsilon_elim(&args_left[1..], cmd, true)
         } else if cmd == "minimize" {
             cmd_minimize(&args_left[1..])
+        } else if cmd == "determinize" {
+            cmd_determinize(&args_left[1..])
+        } else if cmd == "equiv" {
+            cmd_equiv(&args_left[1..])
+        } else if cmd == "equiv_regex" {
+            cmd_equiv_regex(&args_left[1..])
+        } else if cmd == "equiv_regex_fsm" {
+            cmd_equiv_regex_fsm(&args_left[1..])
+        } else if cmd == "accepts" {
+            cmd_accepts(&args_left[1..])
+        } else if cmd == "words" {
+            cmd_words(&args_left[1..])
+        } else if cmd == "words_regex" {
+            cmd_words_regex(&args_left[1..])
+        } else if cmd == "lang_info" {
+            cmd_lang_info(&args_left[1..])
+        } else if cmd == "lang_info_regex" {
+            cmd_lang_info_regex(&args_left[1..])
+        } else if cmd == "grammar_to_fsm" {
+            cmd_grammar_to_fsm(&args_left[1..])
+        } else if cmd == "fsm_to_grammar" {
+            cmd_fsm_to_grammar(&args_left[1..])
+        } else if cmd == "glushkov" {
+            cmd_glushkov(&args_left[1..])
+        } else if cmd == "snf" {
+            cmd_snf(&args_left[1..])
+        } else if cmd == "regex_info" {
+            cmd_regex_info(&args_left[1..])
+        } else if cmd == "ambiguous_regex" {
+            cmd_ambiguous_regex(&args_left[1..])
+        } else if cmd == "simplify_regex" {
+            cmd_simplify_regex(&args_left[1..])
+        } else if cmd == "reverse" {
+            cmd_reverse(&args_left[1..])
+        } else if cmd == "reverse_regex" {
+            cmd_reverse_regex(&args_left[1..])
+        } else if cmd == "left_quotient" || cmd == "right_quotient" {
+            cmd_quotient(&args_left[1..], cmd)
+        } else if cmd == "prefixes" {
+            cmd_closure(&args_left[1..], cmd, Closure::Prefix)
+        } else if cmd == "suffixes" {
+            cmd_closure(&args_left[1..], cmd, Closure::Suffix)
+        } else if cmd == "factors" {
+            cmd_closure(&args_left[1..], cmd, Closure::Factor)
+        } else if cmd == "substitute" {
+            cmd_substitute(&args_left[1..])
+        } else if cmd == "includes" {
+            cmd_includes(&args_left[1..])
+        } else if cmd == "intersect" {
+            cmd_bool_op(&args_left[1..], cmd, BoolOp::Intersection)
+        } else if cmd == "union" {
+            cmd_bool_op(&args_left[1..], cmd, BoolOp::Union)
+        } else if cmd == "difference" {
+            cmd_bool_op(&args_left[1..], cmd, BoolOp::Difference)
+        } else if cmd == "complement" {
+            cmd_complement(&args_left[1..])
+        } else if cmd == "trim" {
+            cmd_trim(&args_left[1..])
         } else if cmd == "help" || cmd == "-h" || cmd == "--help" {
             banner();
             help();
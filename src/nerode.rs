@@ -203,7 +203,7 @@ impl DotFormat for MinimizedStateLabel {
 
 impl MinimizedMachine {
     pub fn from_machine_and_equiv_sets(m: &Machine, sets: &[HashSet<i32>]) -> MinimizedMachine {
-        let sorted_sets: Vec<_> = sets.iter().sorted_by_key(|s| s.iter().min()).collect();
+        let sorted_sets: Vec<_> = sets.iter().filter(|s| !s.is_empty()).sorted_by_key(|s| s.iter().min()).collect();
         let old_to_new: HashMap<i32, i32> = sorted_sets.iter().enumerate().flat_map(|(i, s)| {
             s.iter().map(|j| (*j, i as i32)).collect::<Vec<_>>()
         }).collect();
@@ -222,3 +222,35 @@ impl MinimizedMachine {
         MinimizedMachine{label: m.label, states: new_states}
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::*;
+    use super::*;
+
+    fn nerode_minimized(fsm: &Machine) -> MinimizedMachine {
+        let mut dist = fsm.dist_table_len_0();
+        while fsm.dist_table_update(&mut dist) > 0 { }
+        MinimizedMachine::from_machine_and_equiv_sets(fsm, &fsm.cliques(&dist))
+    }
+
+    #[test]
+    fn empty_language_does_not_panic() {
+        let mut fsm = load_machine("tests/fsm_min_empty_lang.txt");
+        assert_eq!(fsm.shortest_word(), None);
+        fsm.trim();
+        assert!(fsm.states.is_empty());
+        assert!(nerode_minimized(&fsm).states.is_empty());
+        let m = Machine::empty_language(fsm.label);
+        assert_eq!(m.initial_states_ids(), vec![0]);
+        assert!(m.final_states_ids().is_empty());
+    }
+
+    #[test]
+    fn minimization_is_idempotent() {
+        let mut fsm = load_machine("tests/fsm_min_2025-02-12.txt");
+        fsm.trim();
+        let m = Machine::from_machine(nerode_minimized(&fsm));
+        assert_eq!(nerode_minimized(&m).states.len(), m.states.len());
+    }
+}
@@ -2,6 +2,7 @@ use std::mem::replace;
 
 use crate::lexer::*;
 use crate::mnet::*;
+use crate::grammar::*;
 
 pub struct Parser {
     lexer: Lexer,
@@ -138,6 +139,46 @@ impl Parser {
         Some(MachineNet{machines})
     }
 
+    fn parse_rules(&mut self, lhs: char, rules: &mut Vec<Rule>) -> Option<()> {
+        expect!(self, TokenValue::RArrow, "expected -> after the left hand side of the rule");
+        loop {
+            let mut rhs: Vec<char> = vec![];
+            while let token!(TokenValue::Ident(c)) = self.lookahead {
+                self.advance();
+                if c != '_' {
+                    rhs.push(c);
+                }
+            }
+            rules.push(Rule{ lhs, rhs });
+            if accept!(self, TokenValue::Bar).is_none() {
+                break;
+            }
+        }
+        expect!(self, TokenValue::Semi, "expected semicolon");
+        Some(())
+    }
+
+    fn parse_grammar(&mut self) -> Option<Grammar> {
+        let mut rules: Vec<Rule> = Vec::new();
+        expect!(self, TokenValue::KwGrammar, "expected a grammar");
+        expect!(self, TokenValue::LBrace, "expected a grammar body enclosed by {}");
+        while let token!(TokenValue::Ident(lhs)) = self.lookahead {
+            self.advance();
+            self.parse_rules(lhs, &mut rules)?;
+        }
+        expect!(self, TokenValue::RBrace, "expected a rule");
+        Some(Grammar{ rules })
+    }
+
+    pub fn parse_grammar_file(&mut self) -> Option<Grammar> {
+        if let Some(g) = self.parse_grammar() {
+            expect!(self, TokenValue::EndOfFile, "expected end of file");
+            Some(g)
+        } else {
+            None
+        }
+    }
+
     pub fn parse_mnet_file(&mut self) -> Option<MachineNet> {
         if let Some(m) = self.parse_mnet() {
             expect!(self, TokenValue::EndOfFile, "expected end of file");
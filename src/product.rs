@@ -0,0 +1,179 @@
+use std::collections::*;
+use std::fmt;
+
+use crate::fsm::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoolOp {
+    Intersection,
+    Union,
+    Difference,
+    SymmetricDifference
+}
+
+impl BoolOp {
+    fn apply(&self, a: bool, b: bool) -> bool {
+        match self {
+            BoolOp::Intersection => a && b,
+            BoolOp::Union => a || b,
+            BoolOp::Difference => a && !b,
+            BoolOp::SymmetricDifference => a != b
+        }
+    }
+
+    fn is_dead(&self, label: &ProductStateLabel) -> bool {
+        match self {
+            BoolOp::Intersection => label.left.is_none() || label.right.is_none(),
+            BoolOp::Difference => label.left.is_none(),
+            BoolOp::Union | BoolOp::SymmetricDifference => label.left.is_none() && label.right.is_none()
+        }
+    }
+}
+
+// A missing component stands for the implicit sink state of an incomplete machine
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ProductStateLabel {
+    pub left: Option<i32>,
+    pub right: Option<i32>
+}
+
+pub type ProductState = BaseState<ProductStateLabel, char>;
+pub type ProductMachine = BaseMachine<char, ProductStateLabel, char>;
+
+impl fmt::Display for ProductStateLabel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fmt_id = |id: Option<i32>| id.map_or("⊥".to_string(), |id| id.to_string());
+        write!(f, "({}, {})", fmt_id(self.left), fmt_id(self.right))
+    }
+}
+
+impl DotFormat for ProductStateLabel {
+    fn to_dot(&self, _: bool) -> String {
+        format!("\"{self}\"")
+    }
+}
+
+impl ProductMachine {
+    fn insert(&mut self, label: ProductStateLabel, is_initial: bool, is_final: bool) -> i32 {
+        if let Some(s) = self.states.iter().find(|s| s.label == label) {
+            return s.id;
+        }
+        let id = self.states.len() as i32;
+        self.states.push(ProductState{ id, label, transitions: Vec::new(), is_initial, is_final });
+        id
+    }
+}
+
+impl Machine {
+    pub fn product(&self, other: &Machine, op: BoolOp) -> ProductMachine {
+        let dfa_1 = self.determinized();
+        let dfa_2 = other.determinized();
+        let mut alphabet = dfa_1.alphabet();
+        alphabet.extend(dfa_2.alphabet());
+        alphabet.sort();
+        alphabet.dedup();
+
+        let is_final = |label: &ProductStateLabel| {
+            let final_1 = label.left.is_some_and(|sid| dfa_1.lookup_state(sid).is_final);
+            let final_2 = label.right.is_some_and(|sid| dfa_2.lookup_state(sid).is_final);
+            op.apply(final_1, final_2)
+        };
+
+        let mut res = ProductMachine::new(self.label);
+        let ini = ProductStateLabel{
+            left: dfa_1.initial_states_ids().first().cloned(),
+            right: dfa_2.initial_states_ids().first().cloned()
+        };
+        let mut worklist = VecDeque::from([res.insert(ini, true, is_final(&ini))]);
+        let mut visited: HashSet<i32> = HashSet::new();
+        while let Some(state_id) = worklist.pop_front() {
+            if visited.contains(&state_id) {
+                continue;
+            }
+            visited.insert(state_id);
+
+            let label = res.lookup_state(state_id).label;
+            let xions: Vec<_> = alphabet.iter().filter_map(|c| {
+                let next = ProductStateLabel{
+                    left: label.left.and_then(|sid| dfa_1.next_state(sid, *c)),
+                    right: label.right.and_then(|sid| dfa_2.next_state(sid, *c))
+                };
+                if op.is_dead(&next) {
+                    None
+                } else {
+                    let id = res.insert(next, false, is_final(&next));
+                    Some(Transition{ label: *c, dest_id: id })
+                }
+            }).collect();
+            worklist.extend(xions.iter().map(|xion| xion.dest_id));
+            res.lookup_state_mut(state_id).transitions = xions;
+        }
+        res
+    }
+
+    pub fn complement(&self, alphabet: &[char]) -> Machine {
+        let mut res = self.determinized();
+        let mut full_alphabet = res.alphabet();
+        full_alphabet.extend(alphabet);
+        full_alphabet.sort();
+        full_alphabet.dedup();
+
+        let is_complete = res.states.iter().all(|s| {
+            full_alphabet.iter().all(|c| s.transitions.iter().any(|t| t.label == *c))
+        });
+        let sink_id = res.states.iter().map(|s| s.id).max().unwrap_or(-1) + 1;
+        if !is_complete {
+            res.states.push(State{
+                id: sink_id,
+                label: StateLabel{ id: sink_id, m_name: res.label },
+                transitions: vec![],
+                is_initial: false,
+                is_final: false
+            });
+        }
+        for s in &mut res.states {
+            for c in &full_alphabet {
+                if !s.transitions.iter().any(|t| t.label == *c) {
+                    s.transitions.push(Transition{ label: *c, dest_id: sink_id });
+                }
+            }
+            s.is_final = !s.is_final;
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::*;
+    use super::*;
+
+    fn product(re1: &str, re2: &str, op: BoolOp) -> Machine {
+        Machine::from_machine(regex_machine(re1).product(&regex_machine(re2), op))
+    }
+
+    #[test]
+    fn boolean_operations() {
+        let (has_a, has_b) = ("(a|b)*a(a|b)*", "(a|b)*b(a|b)*");
+        assert_eq!(product(has_a, has_b, BoolOp::Intersection).words(3, 4), vec!["ab", "ba", "aab", "aba"]);
+        assert_equivalent(&product("a*", "b*", BoolOp::Union), &regex_machine("a*|b*"));
+        assert_equivalent(&product("(a|b)*", "a*", BoolOp::Difference), &regex_machine(has_b));
+        assert_equivalent(&product("a*", "(aa)*", BoolOp::SymmetricDifference), &regex_machine("a(aa)*"));
+    }
+
+    #[test]
+    fn complement_laws() {
+        let m = regex_machine("(ab|b)*");
+        let comp = m.complement(&[]);
+        assert_equivalent(&comp.complement(&[]), &m);
+        assert!(m.product(&comp, BoolOp::Intersection).shortest_word().is_none());
+        let union = Machine::from_machine(comp.product(&m, BoolOp::Union));
+        assert!(union.complement(&[]).shortest_word().is_none());
+    }
+
+    #[test]
+    fn complement_over_larger_alphabet() {
+        let comp = regex_machine("a*").complement(&['b']);
+        assert_eq!(comp.words(2, 10), vec!["b", "ab", "ba", "bb"]);
+    }
+}
@@ -33,6 +33,14 @@ pub fn set_prod(a: &NumTermSet, b: &NumTermSet) -> NumDigramsSet {
     res
 }
 
+pub fn format_word(w: &str) -> String {
+    if w.is_empty() {
+        "ε".to_string()
+    } else {
+        format!("\"{w}\"")
+    }
+}
+
 pub type NumFollowersMap = HashMap<NumTerm, NumTermSet>;
 
 pub trait NumLocalSets {
@@ -1,5 +1,8 @@
 pub mod parser;
 mod formatter;
+mod simplify;
+pub mod metrics;
+mod star_normal_form;
 
 use crate::reg_lang::*;
 
@@ -12,7 +15,17 @@ pub enum Regex {
     Union(Box<Regex>, Box<Regex>),
     Concat(Box<Regex>, Box<Regex>),
     Star(Box<Regex>),
-    Plus(Box<Regex>)
+    Plus(Box<Regex>),
+    Complement(Box<Regex>),
+    Intersection(Box<Regex>, Box<Regex>)
+}
+
+// Union of expressions where None stands for the empty language
+pub fn union_opt(r1: Option<Regex>, r2: Option<Regex>) -> Option<Regex> {
+    match (r1, r2) {
+        (Some(r1), Some(r2)) => Some(Regex::Union(Box::new(r1), Box::new(r2))),
+        (r1, r2) => r1.or(r2)
+    }
 }
 
 impl NumLocalSets for Regex {
@@ -23,7 +36,9 @@ impl NumLocalSets for Regex {
             Regex::Union(r1, r2) => r1.nullable() || r2.nullable(),
             Regex::Concat(r1, r2) => r1.nullable() && r2.nullable(),
             Regex::Plus(r1) => r1.nullable(),
-            Regex::Star(_) => true
+            Regex::Star(_) => true,
+            Regex::Complement(r1) => !r1.nullable(),
+            Regex::Intersection(r1, r2) => r1.nullable() && r2.nullable()
         }
     }
 
@@ -32,13 +47,15 @@ impl NumLocalSets for Regex {
             Regex::Null => HashSet::new(),
             Regex::Literal(t) => NumTermSet::from([*t]),
             Regex::Union(r1, r2)
-            | Regex::Concat(r1, r2) => {
+            | Regex::Concat(r1, r2)
+            | Regex::Intersection(r1, r2) => {
                 let mut set = r1.all_numbered();
                 set.extend(r2.all_numbered());
                 set
             }
             Regex::Plus(r1)
-            | Regex::Star(r1) => r1.all_numbered()
+            | Regex::Star(r1)
+            | Regex::Complement(r1) => r1.all_numbered()
         }
     }
 
@@ -59,7 +76,9 @@ impl NumLocalSets for Regex {
                 set
             }
             Regex::Plus(r1)
-            | Regex::Star(r1) => r1.numbered_initials()
+            | Regex::Star(r1) => r1.numbered_initials(),
+            Regex::Complement(_)
+            | Regex::Intersection(_, _) => panic!("local sets of an extended regular expression")
         }
     }
 
@@ -80,7 +99,9 @@ impl NumLocalSets for Regex {
                 set
             }
             Regex::Plus(r1)
-            | Regex::Star(r1) => r1.numbered_finals()
+            | Regex::Star(r1) => r1.numbered_finals(),
+            Regex::Complement(_)
+            | Regex::Intersection(_, _) => panic!("local sets of an extended regular expression")
         }
     }
 
@@ -103,6 +124,8 @@ impl NumLocalSets for Regex {
                 res.extend(set_prod(&r1.numbered_finals(), &r1.numbered_initials()));
                 res
             }
+            Regex::Complement(_)
+            | Regex::Intersection(_, _) => panic!("local sets of an extended regular expression")
         }
     }
 }
@@ -8,6 +8,7 @@ enum RegexFmtCharClass {
     OpenGroup(char),
     ClosedGroup(char),
     UnOp(char),
+    PreOp(char),
     BinOp(char)
 }
 
@@ -16,8 +17,10 @@ impl Regex {
         match self {
             Regex::Null | Regex::Literal(_) => 0,
             Regex::Star(_) | Regex::Plus(_) => -1,
-            Regex::Concat(_, _) => -2,
-            Regex::Union(_, _) => -3
+            Regex::Complement(_) => -2,
+            Regex::Concat(_, _) => -3,
+            Regex::Intersection(_, _) => -4,
+            Regex::Union(_, _) => -5
         }
     }
 }
@@ -42,13 +45,14 @@ impl RegexFormatter {
     fn write(&mut self, next: RegexFmtCharClass) {
         let space = if self.style == Plain || self.style == Dot {
             false
-        } else if let RegexFmtCharClass::Ini = self.prev {
+        } else if let RegexFmtCharClass::Ini | RegexFmtCharClass::PreOp(_) = self.prev {
             false
         } else {
             match next {
                 RegexFmtCharClass::Ini => true,
                 RegexFmtCharClass::Literal(_, _) => true,
-                RegexFmtCharClass::OpenGroup(_) => {
+                RegexFmtCharClass::OpenGroup(_)
+                | RegexFmtCharClass::PreOp(_) => {
                     !matches!(self.prev, RegexFmtCharClass::OpenGroup(_))
                 }
                 RegexFmtCharClass::ClosedGroup(_) => {
@@ -82,6 +86,7 @@ impl RegexFormatter {
             RegexFmtCharClass::OpenGroup(c)
                 | RegexFmtCharClass::ClosedGroup(c)
                 | RegexFmtCharClass::UnOp(c)
+                | RegexFmtCharClass::PreOp(c)
                 | RegexFmtCharClass::BinOp(c) => self.buf.push(c)
         };
         self.prev = next;
@@ -122,6 +127,18 @@ impl RegexFormatter {
                 self.write(RegexFmtCharClass::BinOp('|'));
                 self.fmt_child(re, rhs);
             }
+            // The ASCII operators would need escaping in graphviz labels
+            Regex::Complement(re2) => {
+                let dot = self.style == Dot || self.style == DotNumbered;
+                self.write(RegexFmtCharClass::PreOp(if dot { '¬' } else { '~' }));
+                self.fmt_child(re, re2);
+            }
+            Regex::Intersection(lhs, rhs) => {
+                let dot = self.style == Dot || self.style == DotNumbered;
+                self.fmt_child(re, lhs);
+                self.write(RegexFmtCharClass::BinOp(if dot { '∩' } else { '&' }));
+                self.fmt_child(re, rhs);
+            }
         }
     }
 }
@@ -0,0 +1,129 @@
+use crate::regex::*;
+
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegexMetric {
+    Length,
+    Literals,
+    Size,
+    StarHeight
+}
+
+impl RegexMetric {
+    pub fn from_name(name: &str) -> Option<RegexMetric> {
+        match name {
+            "len" => Some(RegexMetric::Length),
+            "literals" => Some(RegexMetric::Literals),
+            "size" => Some(RegexMetric::Size),
+            "star" => Some(RegexMetric::StarHeight),
+            _ => None
+        }
+    }
+
+    pub fn measure(&self, re: &Regex) -> usize {
+        match self {
+            RegexMetric::Length => re.to_string().chars().count(),
+            RegexMetric::Literals => re.literal_count(),
+            RegexMetric::Size => re.size(),
+            RegexMetric::StarHeight => re.star_height()
+        }
+    }
+}
+
+impl fmt::Display for RegexMetric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegexMetric::Length => write!(f, "length"),
+            RegexMetric::Literals => write!(f, "literal count"),
+            RegexMetric::Size => write!(f, "size"),
+            RegexMetric::StarHeight => write!(f, "star height")
+        }
+    }
+}
+
+impl Regex {
+    // The cross operator counts as a star, as r+ = r r*
+    pub fn star_height(&self) -> usize {
+        match self {
+            Regex::Null | Regex::Literal(_) => 0,
+            Regex::Union(r1, r2)
+            | Regex::Concat(r1, r2)
+            | Regex::Intersection(r1, r2) => r1.star_height().max(r2.star_height()),
+            Regex::Star(r1)
+            | Regex::Plus(r1) => r1.star_height() + 1,
+            Regex::Complement(r1) => r1.star_height()
+        }
+    }
+
+    // Occurrences of letters, also known as the alphabetic width; the empty
+    // string is not a literal
+    pub fn literal_count(&self) -> usize {
+        match self {
+            Regex::Null => 0,
+            Regex::Literal(_) => 1,
+            Regex::Union(r1, r2)
+            | Regex::Concat(r1, r2)
+            | Regex::Intersection(r1, r2) => r1.literal_count() + r2.literal_count(),
+            Regex::Star(r1)
+            | Regex::Plus(r1)
+            | Regex::Complement(r1) => r1.literal_count()
+        }
+    }
+
+    pub fn operator_count(&self) -> usize {
+        match self {
+            Regex::Null | Regex::Literal(_) => 0,
+            Regex::Union(r1, r2)
+            | Regex::Concat(r1, r2)
+            | Regex::Intersection(r1, r2) => r1.operator_count() + r2.operator_count() + 1,
+            Regex::Star(r1)
+            | Regex::Plus(r1)
+            | Regex::Complement(r1) => r1.operator_count() + 1
+        }
+    }
+
+    // Occurrences of letters, of the empty string and of operators
+    pub fn size(&self) -> usize {
+        match self {
+            Regex::Null | Regex::Literal(_) => 1,
+            Regex::Union(r1, r2)
+            | Regex::Concat(r1, r2)
+            | Regex::Intersection(r1, r2) => r1.size() + r2.size() + 1,
+            Regex::Star(r1)
+            | Regex::Plus(r1)
+            | Regex::Complement(r1) => r1.size() + 1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::*;
+    use super::*;
+
+    #[test]
+    fn metrics() {
+        let re = regex("(a|_)(b*c)+");
+        assert_eq!(re.star_height(), 2);
+        assert_eq!(re.operator_count(), 5);
+        assert_eq!(re.literal_count(), 3);
+        assert_eq!(re.size(), 9);
+    }
+
+    #[test]
+    fn empty_string_is_not_a_literal() {
+        let re = regex("_|_a");
+        assert_eq!(RegexMetric::Literals.measure(&re), 1);
+        assert_eq!(RegexMetric::Size.measure(&re), 5);
+        assert_eq!(RegexMetric::Length.measure(&re), 4);
+    }
+
+    #[test]
+    fn metric_names() {
+        for (name, metric) in [("len", RegexMetric::Length), ("literals", RegexMetric::Literals), ("size", RegexMetric::Size), ("star", RegexMetric::StarHeight)] {
+            assert_eq!(RegexMetric::from_name(name), Some(metric));
+        }
+        assert_eq!(RegexMetric::from_name("width"), None);
+    }
+}
@@ -15,7 +15,14 @@ enum RegexTokenValue {
     LPar,
     RPar,
     LSquare,
-    RSquare
+    RSquare,
+    Range,
+    LBrace,
+    RBrace,
+    Comma,
+    Digit(usize),
+    Complement,
+    Intersection
 }
 
 struct RegexToken {
@@ -45,6 +52,20 @@ impl RegexToken {
             RegexTokenValue::LSquare
         } else if c == ']' {
             RegexTokenValue::RSquare
+        } else if c == '-' {
+            RegexTokenValue::Range
+        } else if c == '{' {
+            RegexTokenValue::LBrace
+        } else if c == '}' {
+            RegexTokenValue::RBrace
+        } else if c == ',' {
+            RegexTokenValue::Comma
+        } else if let Some(d) = c.to_digit(10) {
+            RegexTokenValue::Digit(d as usize)
+        } else if c == '~' || c == '¬' {
+            RegexTokenValue::Complement
+        } else if c == '&' || c == '∩' {
+            RegexTokenValue::Intersection
         } else {
             RegexTokenValue::Invalid
         };
@@ -143,6 +164,81 @@ impl RegexParser<'_> {
         }
     }
 
+    fn new_literal(&mut self, c: char) -> Regex {
+        self.lit_counter += 1;
+        Regex::Literal(NumTerm::new(c, self.lit_counter))
+    }
+
+    // Copy of an expression with freshly numbered literals
+    fn renumbered(&mut self, re: &Regex) -> Regex {
+        match re {
+            Regex::Null => Regex::Null,
+            Regex::Literal(t) => self.new_literal(t.c),
+            Regex::Union(r1, r2) => Regex::Union(Box::new(self.renumbered(r1)), Box::new(self.renumbered(r2))),
+            Regex::Concat(r1, r2) => Regex::Concat(Box::new(self.renumbered(r1)), Box::new(self.renumbered(r2))),
+            Regex::Intersection(r1, r2) => Regex::Intersection(Box::new(self.renumbered(r1)), Box::new(self.renumbered(r2))),
+            Regex::Star(r1) => Regex::Star(Box::new(self.renumbered(r1))),
+            Regex::Plus(r1) => Regex::Plus(Box::new(self.renumbered(r1))),
+            Regex::Complement(r1) => Regex::Complement(Box::new(self.renumbered(r1)))
+        }
+    }
+
+    fn parse_number(&mut self) -> Option<usize> {
+        let mut res = expect!(self, RegexTokenValue::Digit(d), "expected a number", { d });
+        while let token!(RegexTokenValue::Digit(d)) = self.lookahead {
+            self.advance();
+            res = res.checked_mul(10).and_then(|n| n.checked_add(d)).or_else(|| {
+                self.emit_error("number too large");
+                None
+            })?;
+        }
+        Some(res)
+    }
+
+    // Parses {n}, {n,} or {n,m}, returning the bounds
+    fn parse_repetition(&mut self) -> Option<(usize, Option<usize>)> {
+        let min = self.parse_number()?;
+        let max = if accept!(self, RegexTokenValue::Comma).is_some() {
+            if let token!(RegexTokenValue::Digit(_)) = self.lookahead {
+                let max = self.parse_number()?;
+                if max < min {
+                    self.emit_error("the upper bound of the repetition is less than the lower bound");
+                    return None;
+                }
+                Some(max)
+            } else {
+                None
+            }
+        } else {
+            Some(min)
+        };
+        expect!(self, RegexTokenValue::RBrace, "mismatched brace");
+        Some((min, max))
+    }
+
+    // r{n,m} is desugared to n copies of r followed by m-n optional copies,
+    // r{n,} to n copies followed by r*
+    fn repeat(&mut self, re: Regex, min: usize, max: Option<usize>) -> Regex {
+        let n_copies = max.unwrap_or(min + 1);
+        if n_copies == 0 {
+            return Regex::Null;
+        }
+        let mut copies = vec![re.clone()];
+        for _ in 1..n_copies {
+            let copy = self.renumbered(&re);
+            copies.push(copy);
+        }
+        copies.into_iter().enumerate().map(|(i, r)| {
+            if i < min {
+                r
+            } else if max.is_none() {
+                Regex::Star(Box::new(r))
+            } else {
+                Regex::Union(Box::new(r), Box::new(Regex::Null))
+            }
+        }).reduce(|acc, r| Regex::Concat(Box::new(acc), Box::new(r))).unwrap()
+    }
+
     fn parse_term(&mut self) -> Option<Regex> {
         if accept!(self, RegexTokenValue::LPar).is_some() {
             let res = self.parse_union()?;
@@ -156,8 +252,19 @@ impl RegexParser<'_> {
             Some(Regex::Null)
         } else if let token!(RegexTokenValue::Literal(c)) = self.lookahead {
             self.advance();
-            self.lit_counter += 1;
-            Some(Regex::Literal(NumTerm::new(c, self.lit_counter)))
+            if accept!(self, RegexTokenValue::Range).is_some() {
+                let last = expect!(self, RegexTokenValue::Literal(c2), "expected a character", { c2 });
+                if last < c {
+                    self.emit_error("empty character range");
+                    return None;
+                }
+                // A range is desugared to the union of its characters
+                Some((c..=last).map(|c| self.new_literal(c)).reduce(|acc, r| {
+                    Regex::Union(Box::new(acc), Box::new(r))
+                }).unwrap())
+            } else {
+                Some(self.new_literal(c))
+            }
         } else {
             self.emit_error("expected a character or a group");
             None
@@ -171,23 +278,35 @@ impl RegexParser<'_> {
                 lhs = Regex::Star(Box::new(lhs));
             } else if accept!(self, RegexTokenValue::Plus).is_some() {
                 lhs = Regex::Plus(Box::new(lhs));
+            } else if accept!(self, RegexTokenValue::LBrace).is_some() {
+                let (min, max) = self.parse_repetition()?;
+                lhs = self.repeat(lhs, min, max);
             } else {
                 break Some(lhs);
             }
         }
     }
 
+    fn parse_complement(&mut self) -> Option<Regex> {
+        if accept!(self, RegexTokenValue::Complement).is_some() {
+            Some(Regex::Complement(Box::new(self.parse_complement()?)))
+        } else {
+            self.parse_star()
+        }
+    }
+
     fn parse_concat(&mut self) -> Option<Regex> {
-        let mut lhs = self.parse_star()?;
+        let mut lhs = self.parse_complement()?;
         loop {
             if accept!(self, RegexTokenValue::Concat).is_some() {
-                let rhs = self.parse_star()?;
+                let rhs = self.parse_complement()?;
                 lhs = Regex::Concat(Box::new(lhs), Box::new(rhs));
             } else if let token!(RegexTokenValue::Literal(_))
                     | token!(RegexTokenValue::Epsilon)
                     | token!(RegexTokenValue::LPar)
-                    | token!(RegexTokenValue::LSquare) = self.lookahead {
-                let rhs = self.parse_star()?;
+                    | token!(RegexTokenValue::LSquare)
+                    | token!(RegexTokenValue::Complement) = self.lookahead {
+                let rhs = self.parse_complement()?;
                 lhs = Regex::Concat(Box::new(lhs), Box::new(rhs));
             } else {
                 break Some(lhs);
@@ -195,11 +314,23 @@ impl RegexParser<'_> {
         }
     }
 
-    fn parse_union(&mut self) -> Option<Regex> {
+    fn parse_intersection(&mut self) -> Option<Regex> {
         let mut lhs = self.parse_concat()?;
         loop {
-            if accept!(self, RegexTokenValue::Union).is_some() {
+            if accept!(self, RegexTokenValue::Intersection).is_some() {
                 let rhs = self.parse_concat()?;
+                lhs = Regex::Intersection(Box::new(lhs), Box::new(rhs));
+            } else {
+                break Some(lhs);
+            }
+        }
+    }
+
+    fn parse_union(&mut self) -> Option<Regex> {
+        let mut lhs = self.parse_intersection()?;
+        loop {
+            if accept!(self, RegexTokenValue::Union).is_some() {
+                let rhs = self.parse_intersection()?;
                 lhs = Regex::Union(Box::new(lhs), Box::new(rhs));
             } else {
                 break Some(lhs);
@@ -221,3 +352,37 @@ impl RegexParser<'_> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsed(s: &str) -> Option<String> {
+        RegexParser::new(s).parse_regex().map(|re| re.to_string())
+    }
+
+    #[test]
+    fn ranges_and_repetitions() {
+        assert_eq!(parsed("a-c").as_deref(), Some("a|b|c"));
+        assert_eq!(parsed("a{2,3}").as_deref(), Some("aa(a|_)"));
+        assert_eq!(parsed("a{2,}").as_deref(), Some("aaa*"));
+        assert_eq!(parsed("a{0}").as_deref(), Some("_"));
+        assert_eq!(parsed("c-a"), None);
+        assert_eq!(parsed("a{3,1}"), None);
+    }
+
+    #[test]
+    fn complement_and_intersection() {
+        assert_eq!(parsed("¬a∩b").as_deref(), Some("~a&b"));
+        assert_eq!(parsed("(a|b)*&~(a*)").as_deref(), Some("(a|b)*&~a*"));
+        assert_eq!(parsed("~(a|b)").as_deref(), Some("~(a|b)"));
+    }
+
+    #[test]
+    fn repeated_literals_are_renumbered() {
+        let re = RegexParser::new("(ab){2}").parse_regex().unwrap();
+        let mut numbers: Vec<usize> = re.all_numbered().into_iter().map(|t| t.i).collect();
+        numbers.sort();
+        assert_eq!(numbers, vec![1, 2, 3, 4]);
+    }
+}
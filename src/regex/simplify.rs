@@ -0,0 +1,238 @@
+use crate::reg_lang::*;
+use crate::regex::*;
+
+fn collect_union_terms(re: Regex, terms: &mut Vec<Regex>) {
+    match re {
+        Regex::Union(r1, r2) => {
+            collect_union_terms(*r1, terms);
+            collect_union_terms(*r2, terms);
+        }
+        re => terms.push(re)
+    }
+}
+
+fn collect_concat_factors(re: Regex, factors: &mut Vec<Regex>) {
+    match re {
+        Regex::Concat(r1, r2) => {
+            collect_concat_factors(*r1, factors);
+            collect_concat_factors(*r2, factors);
+        }
+        re => factors.push(re)
+    }
+}
+
+fn same(r1: &Regex, r2: &Regex) -> bool {
+    r1.to_string() == r2.to_string()
+}
+
+// Merges two adjacent factors of a concatenation, when they are iterations of
+// the same expression: r r* = r* r = r+ and r* r* = r*
+fn merge_factors(r1: &Regex, r2: &Regex) -> Option<Regex> {
+    match (r1, r2) {
+        (Regex::Star(x), Regex::Star(y)) if same(x, y) => Some(r1.clone()),
+        (Regex::Plus(x), Regex::Star(y))
+        | (Regex::Star(x), Regex::Plus(y)) if same(x, y) => Some(Regex::Plus(x.clone())),
+        (x, Regex::Star(y))
+        | (Regex::Star(y), x) if same(x, y) => Some(Regex::Plus(y.clone())),
+        _ => None
+    }
+}
+
+// Whether the union term is redundant in presence of the other
+fn absorbed_by(r1: &Regex, r2: &Regex) -> bool {
+    match r2 {
+        Regex::Star(x) => same(r1, x) || matches!(r1, Regex::Plus(y) if same(x, y)),
+        Regex::Plus(x) => same(r1, x),
+        _ => false
+    }
+}
+
+fn simplify_union(re: Regex) -> Regex {
+    let mut raw: Vec<Regex> = vec![];
+    collect_union_terms(re, &mut raw);
+    let mut terms: Vec<Regex> = vec![];
+    for t in raw {
+        collect_union_terms(simplify(t), &mut terms);
+    }
+    let uniq = canonical_terms(terms);
+    let mut res: Vec<Regex> = uniq.iter().filter(|t| !uniq.iter().any(|u| absorbed_by(t, u))).cloned().collect();
+    // ε is redundant next to any nullable term, and ε | r+ = r*
+    if res.len() > 1 && res.iter().any(|t| matches!(t, Regex::Null)) {
+        let plus_pos = res.iter().position(|t| matches!(t, Regex::Plus(_)));
+        if res.iter().any(|t| !matches!(t, Regex::Null) && t.nullable()) {
+            res.retain(|t| !matches!(t, Regex::Null));
+        } else if let Some(i) = plus_pos {
+            if let Regex::Plus(x) = res[i].clone() {
+                res[i] = Regex::Star(x);
+            }
+            res.retain(|t| !matches!(t, Regex::Null));
+        }
+    }
+    union_of(canonical_terms(res))
+}
+
+// Sorts the union terms by their printed form and drops the duplicates, so
+// that unions equal up to associativity, commutativity and idempotence coincide
+fn canonical_terms(terms: Vec<Regex>) -> Vec<Regex> {
+    let mut keyed: Vec<_> = terms.into_iter().map(|r| (r.to_string(), r)).collect();
+    keyed.sort_by(|(k1, _), (k2, _)| k1.cmp(k2));
+    keyed.dedup_by(|(k1, _), (k2, _)| k1 == k2);
+    keyed.into_iter().map(|(_, r)| r).collect()
+}
+
+fn union_of(terms: Vec<Regex>) -> Regex {
+    terms.into_iter().reduce(|acc, r| Regex::Union(Box::new(acc), Box::new(r))).unwrap()
+}
+
+// Rewrites the expression modulo associativity, commutativity and idempotence
+// of the union, and the ε-neutrality of the concatenation
+fn normalize(re: Regex) -> Regex {
+    match re {
+        Regex::Null | Regex::Literal(_) => re,
+        Regex::Union(_, _) => {
+            let mut terms: Vec<Regex> = vec![];
+            collect_union_terms(re, &mut terms);
+            let mut norm_terms: Vec<Regex> = vec![];
+            for t in terms {
+                collect_union_terms(normalize(t), &mut norm_terms);
+            }
+            union_of(canonical_terms(norm_terms))
+        }
+        Regex::Concat(r1, r2) => {
+            match (normalize(*r1), normalize(*r2)) {
+                (Regex::Null, r2) => r2,
+                (r1, Regex::Null) => r1,
+                (Regex::Concat(r11, r12), r2) => {
+                    normalize(Regex::Concat(r11, Box::new(Regex::Concat(r12, Box::new(r2)))))
+                }
+                (r1, r2) => Regex::Concat(Box::new(r1), Box::new(r2))
+            }
+        }
+        Regex::Star(r1) => {
+            match normalize(*r1) {
+                Regex::Null => Regex::Null,
+                Regex::Star(r11) => Regex::Star(r11),
+                r1 => Regex::Star(Box::new(r1))
+            }
+        }
+        Regex::Plus(r1) => Regex::Plus(Box::new(normalize(*r1))),
+        Regex::Complement(r1) => Regex::Complement(Box::new(normalize(*r1))),
+        Regex::Intersection(r1, r2) => Regex::Intersection(Box::new(normalize(*r1)), Box::new(normalize(*r2)))
+    }
+}
+
+fn simplify_concat(re: Regex) -> Regex {
+    let mut raw: Vec<Regex> = vec![];
+    collect_concat_factors(re, &mut raw);
+    let mut factors: Vec<Regex> = vec![];
+    for f in raw {
+        collect_concat_factors(simplify(f), &mut factors);
+    }
+    let mut res: Vec<Regex> = vec![];
+    for f in factors {
+        if matches!(f, Regex::Null) {
+            continue;
+        }
+        if let Some(merged) = res.last().and_then(|prev| merge_factors(prev, &f)) {
+            res.pop();
+            res.push(merged);
+        } else {
+            res.push(f);
+        }
+    }
+    res.into_iter().reduce(|acc, r| Regex::Concat(Box::new(acc), Box::new(r))).unwrap_or(Regex::Null)
+}
+
+// The body of an iteration, where ε terms are useless and iterated terms can
+// be replaced by their argument: (ε | r+ | s)* = (r | s)*
+fn simplify_star_body(re: Regex) -> Regex {
+    let mut terms: Vec<Regex> = vec![];
+    collect_union_terms(re, &mut terms);
+    let terms: Vec<Regex> = terms.into_iter().filter_map(|t| {
+        match t {
+            Regex::Null => None,
+            Regex::Star(x) | Regex::Plus(x) => Some(*x),
+            t => Some(t)
+        }
+    }).collect();
+    if terms.is_empty() {
+        Regex::Null
+    } else {
+        simplify(union_of(terms))
+    }
+}
+
+fn simplify(re: Regex) -> Regex {
+    match re {
+        Regex::Null | Regex::Literal(_) => re,
+        Regex::Union(_, _) => simplify_union(re),
+        Regex::Concat(_, _) => simplify_concat(re),
+        Regex::Star(r1) => {
+            match simplify_star_body(simplify(*r1)) {
+                Regex::Null => Regex::Null,
+                r1 => Regex::Star(Box::new(r1))
+            }
+        }
+        Regex::Plus(r1) => {
+            match simplify(*r1) {
+                Regex::Null => Regex::Null,
+                r1 @ (Regex::Plus(_) | Regex::Star(_)) => r1,
+                r1 if r1.nullable() => simplify(Regex::Star(Box::new(r1))),
+                r1 => Regex::Plus(Box::new(r1))
+            }
+        }
+        Regex::Complement(r1) => {
+            match simplify(*r1) {
+                Regex::Complement(r11) => *r11,
+                r1 => Regex::Complement(Box::new(r1))
+            }
+        }
+        Regex::Intersection(r1, r2) => {
+            let (r1, r2) = (simplify(*r1), simplify(*r2));
+            if same(&r1, &r2) {
+                r1
+            } else {
+                Regex::Intersection(Box::new(r1), Box::new(r2))
+            }
+        }
+    }
+}
+
+impl Regex {
+    pub fn simplify(&self) -> Regex {
+        simplify(self.clone())
+    }
+
+    // Lighter than simplify, and enough to make the derivatives finitely many
+    pub fn normalized(&self) -> Regex {
+        normalize(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ext_regex::*;
+    use crate::test_util::*;
+
+    #[test]
+    fn rewrites() {
+        for (re, expected) in [("aa*", "a+"), ("_|a+", "a*"), ("(a*)*", "a*"), ("a|a", "a"), ("(_|a+|b)*", "(a|b)*"), ("a*a*b_", "a*b")] {
+            assert_eq!(regex(re).simplify().to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn union_terms_in_canonical_order() {
+        for (re, expected) in [("(a|b)c|(b|a)c", "(a|b)c"), ("(a|b)*|(b|a)*", "(a|b)*"), ("c|b|a", "a|b|c")] {
+            assert_eq!(regex(re).simplify().to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn preserves_language() {
+        for re in ["(a|_)(a|_)*b", "(a+|b*)+c", "((a*b*)*|_)(ab|ab)", "(a|ab)(_|b)*", "a*(a|b*)+", "(ab)+|_|(ab)*"] {
+            let re = regex(re);
+            assert_equivalent(&regex_to_machine(&re.simplify()), &regex_to_machine(&re));
+        }
+    }
+}
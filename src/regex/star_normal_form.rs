@@ -0,0 +1,97 @@
+use crate::reg_lang::*;
+use crate::regex::*;
+
+impl Regex {
+    // In star normal form the argument H of every iteration is not nullable, and
+    // no last position of H is followed by a first position of H within H itself
+    pub fn is_star_normal_form(&self) -> bool {
+        match self {
+            Regex::Null | Regex::Literal(_) => true,
+            Regex::Union(r1, r2)
+            | Regex::Concat(r1, r2)
+            | Regex::Intersection(r1, r2) => r1.is_star_normal_form() && r2.is_star_normal_form(),
+            Regex::Complement(r1) => r1.is_star_normal_form(),
+            Regex::Star(r1)
+            | Regex::Plus(r1) => {
+                let (ini, fin) = (r1.numbered_initials(), r1.numbered_finals());
+                let loops = r1.numbered_digrams().iter().any(|(t1, t2)| fin.contains(t1) && ini.contains(t2));
+                !r1.nullable() && !loops && r1.is_star_normal_form()
+            }
+        }
+    }
+
+    // The circle transformation removes the empty string and the iterations
+    // from the top level of the expression, preserving the language of its star.
+    // None stands for the empty language.
+    fn snf_circle(&self) -> Option<Regex> {
+        match self {
+            Regex::Null => None,
+            Regex::Literal(_) => Some(self.clone()),
+            Regex::Union(r1, r2) => union_opt(r1.snf_circle(), r2.snf_circle()),
+            // Under an iteration, (F G)* = (F° G)* when only G is nullable, which
+            // removes the redundant loops of a cross operator in F, and vice versa
+            Regex::Concat(r1, r2) => {
+                let (lhs, rhs) = match (r1.nullable(), r2.nullable()) {
+                    (true, true) => return union_opt(r1.snf_circle(), r2.snf_circle()),
+                    (false, true) => (r1.snf_circle()?, r2.to_star_normal_form()),
+                    (true, false) => (r1.to_star_normal_form(), r2.snf_circle()?),
+                    (false, false) => (r1.to_star_normal_form(), r2.to_star_normal_form())
+                };
+                Some(Regex::Concat(Box::new(lhs), Box::new(rhs)))
+            }
+            Regex::Star(r1)
+            | Regex::Plus(r1) => r1.snf_circle(),
+            Regex::Complement(_)
+            | Regex::Intersection(_, _) => panic!("star normal form of an extended regular expression")
+        }
+    }
+
+    // Brüggemann-Klein's transformation, preserving the language and the numbering
+    pub fn to_star_normal_form(&self) -> Regex {
+        match self {
+            Regex::Null | Regex::Literal(_) => self.clone(),
+            Regex::Union(r1, r2) => Regex::Union(Box::new(r1.to_star_normal_form()), Box::new(r2.to_star_normal_form())),
+            Regex::Concat(r1, r2) => Regex::Concat(Box::new(r1.to_star_normal_form()), Box::new(r2.to_star_normal_form())),
+            Regex::Star(r1) => r1.snf_circle().map_or(Regex::Null, |r| Regex::Star(Box::new(r))),
+            // r+ is r* when r is nullable
+            Regex::Plus(r1) => {
+                let circle = r1.snf_circle();
+                if r1.nullable() {
+                    circle.map_or(Regex::Null, |r| Regex::Star(Box::new(r)))
+                } else {
+                    Regex::Plus(Box::new(circle.unwrap()))
+                }
+            }
+            Regex::Complement(_)
+            | Regex::Intersection(_, _) => panic!("star normal form of an extended regular expression")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ext_regex::*;
+    use crate::test_util::*;
+
+    #[test]
+    fn star_normal_form_check() {
+        for re in ["(a|b)*", "(ab)*", "a*b*", "(a+b)*", "_"] {
+            assert!(regex(re).is_star_normal_form(), "{re}");
+        }
+        for re in ["(a*b*)*", "(a|_)*", "(a*)*", "(a+|b)*", "(a|b)*+"] {
+            assert!(!regex(re).is_star_normal_form(), "{re}");
+        }
+    }
+
+    #[test]
+    fn star_normal_form_round_trip() {
+        let cases = ["(a*b*)*", "(a|_)*", "((a|b)*c*)+", "(a+b*)+", "(a*|b+)*c", "((ab)*|(a*b)+)*", "a*", "_"];
+        for re in cases.map(regex) {
+            let snf = re.to_star_normal_form();
+            assert!(snf.is_star_normal_form(), "{re}");
+            assert_equivalent(&regex_to_machine(&snf), &regex_to_machine(&re));
+            assert_eq!(snf.to_star_normal_form().to_string(), snf.to_string());
+        }
+        assert_eq!(regex("(a*b*)*").to_star_normal_form().to_string(), "(a|b)*");
+    }
+}
@@ -0,0 +1,80 @@
+use crate::fsm::*;
+use crate::regex::*;
+
+impl Machine {
+    pub fn reverse(&self) -> Machine {
+        let mut res = Machine::new(self.label);
+        for s in &self.states {
+            res.states.push(State{
+                id: s.id,
+                label: s.label,
+                transitions: vec![],
+                is_initial: s.is_final,
+                is_final: s.is_initial
+            });
+        }
+        for s in &self.states {
+            for t in &s.transitions {
+                res.lookup_state_mut(t.dest_id).transitions.push(Transition{ label: t.label, dest_id: s.id });
+            }
+        }
+        res
+    }
+}
+
+impl Regex {
+    // Literals keep their numbering, so that positions can be matched with the original
+    pub fn reverse(&self) -> Regex {
+        match self {
+            Regex::Null | Regex::Literal(_) => self.clone(),
+            Regex::Union(r1, r2) => Regex::Union(Box::new(r1.reverse()), Box::new(r2.reverse())),
+            Regex::Concat(r1, r2) => Regex::Concat(Box::new(r2.reverse()), Box::new(r1.reverse())),
+            Regex::Star(r1) => Regex::Star(Box::new(r1.reverse())),
+            Regex::Plus(r1) => Regex::Plus(Box::new(r1.reverse())),
+            Regex::Complement(r1) => Regex::Complement(Box::new(r1.reverse())),
+            Regex::Intersection(r1, r2) => Regex::Intersection(Box::new(r1.reverse()), Box::new(r2.reverse()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ext_regex::*;
+    use crate::subset::*;
+    use crate::test_util::*;
+    use super::*;
+
+    const REGEXES: [&str; 4] = ["(a|b)*abb", "(ab|_)(c|d)+", "a(ba)*|(ab)*a", "((a|b)*c)*"];
+
+    #[test]
+    fn reversed_machine_reads_mirror_words() {
+        for re in REGEXES {
+            let m = regex_machine(re);
+            let mut mirror: Vec<String> = m.words(5, usize::MAX).iter().map(|w| w.chars().rev().collect()).collect();
+            mirror.sort_by_key(|w| (w.len(), w.clone()));
+            assert_eq!(m.reverse().words(5, usize::MAX), mirror);
+            assert_equivalent(&m.reverse().reverse(), &m);
+        }
+    }
+
+    #[test]
+    fn reversed_regex_matches_reversed_machine() {
+        for re in REGEXES {
+            assert_equivalent(&regex_machine(re).reverse(), &regex_to_machine(&regex(re).reverse()));
+        }
+    }
+
+    #[test]
+    fn brzozowski_gives_minimal_machine() {
+        for re in REGEXES {
+            let m = regex_machine(re);
+            let dfa = Machine::from_machine(subset_construction(&m.reverse()));
+            let mut min = Machine::from_machine(subset_construction(&dfa.reverse()));
+            min.trim();
+            let mut dfa = m.determinized();
+            dfa.trim();
+            assert_eq!(min.states.len(), dfa.hopcroft_equiv_sets().len());
+            assert_equivalent(&min, &m);
+        }
+    }
+}
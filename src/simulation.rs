@@ -0,0 +1,74 @@
+use crate::fsm::*;
+
+// A run is the initial state followed by the transitions taken
+pub type Run = (i32, Vec<Transition>);
+
+struct RunSearch<'a> {
+    machine: &'a Machine,
+    ini: i32,
+    path: Vec<Transition>,
+    res: Vec<Run>
+}
+
+impl RunSearch<'_> {
+    // eps_visited holds the states reached since the last character was read,
+    // to avoid looping on cycles of spontaneous moves
+    fn visit(&mut self, sid: i32, word: &[char], eps_visited: &mut Vec<i32>) {
+        if eps_visited.contains(&sid) {
+            return;
+        }
+        eps_visited.push(sid);
+        let s = self.machine.lookup_state(sid);
+        if word.is_empty() && s.is_final {
+            self.res.push((self.ini, self.path.clone()));
+        }
+        for t in &s.transitions {
+            self.path.push(*t);
+            if t.is_epsilon() {
+                self.visit(t.dest_id, word, eps_visited);
+            } else if word.first() == Some(&t.label) {
+                self.visit(t.dest_id, &word[1..], &mut vec![]);
+            }
+            self.path.pop();
+        }
+        eps_visited.pop();
+    }
+}
+
+impl Machine {
+    pub fn accepting_runs(&self, word: &str) -> Vec<Run> {
+        let chars: Vec<char> = word.chars().collect();
+        let mut res: Vec<Run> = vec![];
+        for ini in self.initial_states_ids() {
+            let mut search = RunSearch{ machine: self, ini, path: vec![], res: vec![] };
+            search.visit(ini, &chars, &mut vec![]);
+            res.extend(search.res);
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::thompson::*;
+    use crate::test_util::*;
+    use super::*;
+
+    #[test]
+    fn runs_follow_spontaneous_moves() {
+        let fsm = load_machine("tests/fsm_backprop_2015-02-09.txt");
+        let t = |label, dest_id| Transition{ label, dest_id };
+        assert_eq!(fsm.accepting_runs(""), vec![(0, vec![t('_', 1)])]);
+        assert_eq!(fsm.accepting_runs("ab"), vec![(0, vec![t('_', 1), t('a', 2), t('b', 3), t('_', 0), t('_', 1)])]);
+        for word in ["", "a", "b", "ab", "bb", "bab", "abab", "bba"] {
+            assert_eq!(!fsm.accepting_runs(word).is_empty(), fsm.accepts(word), "{word}");
+        }
+    }
+
+    #[test]
+    fn one_run_per_derivation() {
+        let fsm = thompson(&regex("a|a"));
+        assert_eq!(fsm.accepting_runs("a").len(), 2);
+        assert!(fsm.accepting_runs("aa").is_empty());
+    }
+}
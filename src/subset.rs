@@ -0,0 +1,187 @@
+use std::collections::*;
+use std::fmt;
+
+use itertools::Itertools;
+use crate::fsm::*;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SubsetStateLabel {
+    pub original_ids: BTreeSet<i32>
+}
+
+pub type SubsetState = BaseState<SubsetStateLabel, char>;
+pub type SubsetMachine = BaseMachine<char, SubsetStateLabel, char>;
+
+impl fmt::Display for SubsetStateLabel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{{}}}", self.original_ids.iter().join(", "))
+    }
+}
+
+impl DotFormat for SubsetStateLabel {
+    fn to_dot(&self, _: bool) -> String {
+        format!("\"{self}\"")
+    }
+}
+
+impl Machine {
+    pub fn epsilon_closure(&self, ids: &BTreeSet<i32>) -> BTreeSet<i32> {
+        let mut worklist: VecDeque<_> = ids.iter().cloned().collect();
+        let mut res: BTreeSet<i32> = BTreeSet::new();
+        while let Some(sid) = worklist.pop_front() {
+            if !res.insert(sid) {
+                continue;
+            }
+            for t in &self.lookup_state(sid).transitions {
+                if t.is_epsilon() {
+                    worklist.push_back(t.dest_id);
+                }
+            }
+        }
+        res
+    }
+
+    pub fn move_on(&self, ids: &BTreeSet<i32>, c: char) -> BTreeSet<i32> {
+        ids.iter().flat_map(|sid| {
+            self.lookup_state(*sid).transitions.iter().filter_map(|t| {
+                if t.label == c { Some(t.dest_id) } else { None }
+            })
+        }).collect()
+    }
+
+    pub fn run(&self, word: &str) -> Vec<BTreeSet<i32>> {
+        let ini: BTreeSet<i32> = self.initial_states_ids().into_iter().collect();
+        let mut res = vec![self.epsilon_closure(&ini)];
+        for c in word.chars() {
+            let next = self.epsilon_closure(&self.move_on(res.last().unwrap(), c));
+            res.push(next);
+        }
+        res
+    }
+
+    pub fn accepts(&self, word: &str) -> bool {
+        let active = self.run(word).pop().unwrap();
+        active.iter().any(|sid| self.lookup_state(*sid).is_final)
+    }
+
+    pub fn determinized(&self) -> Machine {
+        if self.is_deterministic() {
+            self.clone()
+        } else {
+            Machine::from_machine(subset_construction(self))
+        }
+    }
+}
+
+impl SubsetState {
+    fn new(label: SubsetStateLabel, is_initial: bool, is_final: bool) -> SubsetState {
+        SubsetState{ id: -1, label, transitions: Vec::new(), is_initial, is_final }
+    }
+
+    fn collect_transitions(&self, m: &Machine) -> Vec<char> {
+        let mut res: Vec<char> = self.label.original_ids.iter().flat_map(|sid| {
+            m.lookup_state(*sid).transitions.iter().filter_map(|t| {
+                if t.is_epsilon() { None } else { Some(t.label) }
+            })
+        }).collect();
+        res.sort();
+        res.dedup();
+        res
+    }
+}
+
+impl SubsetMachine {
+    pub fn dump_subsets(&self) {
+        for s in &self.states {
+            eprintln!("{} = {}", s.id, s.label);
+            for t in &s.transitions {
+                eprintln!("  {} -{}-> {}", s.label, t.label, self.lookup_state(t.dest_id).label);
+            }
+        }
+    }
+
+    fn try_lookup_state_by_label(&self, label: &SubsetStateLabel) -> Option<&SubsetState> {
+        self.states.iter().find(|s| s.label == *label)
+    }
+
+    fn insert(&mut self, mut new: SubsetState) -> i32 {
+        if let Some(s) = self.try_lookup_state_by_label(&new.label) {
+            return s.id;
+        }
+        let id = self.states.len() as i32;
+        new.id = id;
+        self.states.push(new);
+        id
+    }
+}
+
+fn subset_state(m: &Machine, ids: &BTreeSet<i32>, is_initial: bool) -> SubsetState {
+    let original_ids = m.epsilon_closure(ids);
+    let is_final = original_ids.iter().any(|sid| m.lookup_state(*sid).is_final);
+    SubsetState::new(SubsetStateLabel{ original_ids }, is_initial, is_final)
+}
+
+pub fn subset_construction(m: &Machine) -> SubsetMachine {
+    let mut res = SubsetMachine::new(m.label);
+    let ini: BTreeSet<i32> = m.initial_states_ids().into_iter().collect();
+
+    let mut worklist = VecDeque::from([res.insert(subset_state(m, &ini, true))]);
+    let mut visited: HashSet<i32> = HashSet::new();
+    while let Some(state_id) = worklist.pop_front() {
+        if visited.contains(&state_id) {
+            continue;
+        }
+        visited.insert(state_id);
+
+        let state = res.lookup_state(state_id);
+        let future_xions = state.collect_transitions(m);
+        let shifts: Vec<_> = future_xions.into_iter().map(|c| {
+            (c, subset_state(m, &m.move_on(&state.label.original_ids, c), false))
+        }).collect();
+        let xions: Vec<_> = shifts.into_iter().map(|(c, new_state)| {
+            let id = res.insert(new_state);
+            Transition{ label: c, dest_id: id }
+        }).collect();
+        worklist.extend(xions.iter().map(|xion| xion.dest_id));
+        res.lookup_state_mut(state_id).transitions = xions;
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::*;
+    use super::*;
+
+    #[test]
+    fn determinized_is_deterministic_and_equivalent() {
+        for file in ["tests/fsm_backprop_2015-02-09.txt", "tests/fsm_backprop_book-3.12.txt", "tests/fsm_bs_2019-09-03.txt"] {
+            let fsm = load_machine(file);
+            let dfa = fsm.determinized();
+            assert!(dfa.is_deterministic());
+            for word in ["", "a", "ab", "ba", "aab", "abab", "bbb"] {
+                assert_eq!(fsm.accepts(word), dfa.accepts(word), "{file}: {word}");
+            }
+        }
+    }
+
+    #[test]
+    fn subsets_are_epsilon_closed() {
+        let fsm = load_machine("tests/fsm_backprop_2015-02-09.txt");
+        let dfa = subset_construction(&fsm);
+        for s in &dfa.states {
+            assert_eq!(fsm.epsilon_closure(&s.label.original_ids), s.label.original_ids);
+        }
+    }
+
+    #[test]
+    fn run_tracks_active_states() {
+        let fsm = regex_machine("ab");
+        let sets = |ids: &[&[i32]]| -> Vec<BTreeSet<i32>> {
+            ids.iter().map(|s| s.iter().cloned().collect()).collect()
+        };
+        assert_eq!(fsm.run("ab"), sets(&[&[0], &[1], &[2]]));
+        assert_eq!(fsm.run("ba"), sets(&[&[0], &[], &[]]));
+        assert!(fsm.accepts("ab") && !fsm.accepts("a"));
+    }
+}
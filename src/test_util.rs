@@ -0,0 +1,27 @@
+use std::path::Path;
+
+use crate::fsm::*;
+use crate::lexer::*;
+use crate::parser::*;
+use crate::regex::*;
+use crate::regex::parser::*;
+use crate::ext_regex::*;
+use crate::equivalence::*;
+
+pub fn regex(s: &str) -> Regex {
+    RegexParser::new(s).parse_regex().unwrap()
+}
+
+pub fn regex_machine(s: &str) -> Machine {
+    regex_to_machine(&regex(s))
+}
+
+pub fn load_machine(file: &str) -> Machine {
+    Parser::new(Lexer::from_path(Path::new(file))).parse_machine_file().unwrap()
+}
+
+pub fn assert_equivalent(m1: &Machine, m2: &Machine) {
+    if let Some((word, _)) = distinguishing_word(m1, m2) {
+        panic!("the machines differ on \"{word}\"");
+    }
+}
@@ -0,0 +1,128 @@
+use std::collections::*;
+
+use crate::fsm::*;
+use crate::regex::*;
+use crate::ext_regex::*;
+
+struct ThompsonBuilder {
+    machine: Machine,
+    // Complements are taken with respect to the alphabet of the whole expression
+    alphabet: Vec<char>
+}
+
+impl ThompsonBuilder {
+    fn new_state(&mut self) -> i32 {
+        let id = self.machine.states.len() as i32;
+        let label = StateLabel{ id, m_name: self.machine.label };
+        self.machine.states.push(State{ id, label, transitions: vec![], is_initial: false, is_final: false });
+        id
+    }
+
+    fn add_transition(&mut self, src_id: i32, label: char, dest_id: i32) {
+        self.machine.lookup_state_mut(src_id).transitions.push(Transition{ label, dest_id });
+    }
+
+    // Copies the machine between the start state and a new final state
+    fn embed(&mut self, m: &Machine, start: i32) -> i32 {
+        let new_ids: HashMap<i32, i32> = m.states.iter().map(|s| (s.id, self.new_state())).collect();
+        let end = self.new_state();
+        for s in &m.states {
+            for t in &s.transitions {
+                self.add_transition(new_ids[&s.id], t.label, new_ids[&t.dest_id]);
+            }
+            if s.is_initial {
+                self.add_transition(start, '_', new_ids[&s.id]);
+            }
+            if s.is_final {
+                self.add_transition(new_ids[&s.id], '_', end);
+            }
+        }
+        end
+    }
+
+    // Returns the final state of the fragment; the start state is shared with the previous one
+    fn build(&mut self, re: &Regex, start: i32) -> i32 {
+        match re {
+            Regex::Null => {
+                let end = self.new_state();
+                self.add_transition(start, '_', end);
+                end
+            }
+            Regex::Literal(t) => {
+                let end = self.new_state();
+                self.add_transition(start, t.c, end);
+                end
+            }
+            Regex::Union(r1, r2) => {
+                let ini_1 = self.new_state();
+                self.add_transition(start, '_', ini_1);
+                let fin_1 = self.build(r1, ini_1);
+                let ini_2 = self.new_state();
+                self.add_transition(start, '_', ini_2);
+                let fin_2 = self.build(r2, ini_2);
+                let end = self.new_state();
+                self.add_transition(fin_1, '_', end);
+                self.add_transition(fin_2, '_', end);
+                end
+            }
+            Regex::Concat(r1, r2) => {
+                let mid = self.build(r1, start);
+                self.build(r2, mid)
+            }
+            Regex::Star(r1) | Regex::Plus(r1) => {
+                let ini_1 = self.new_state();
+                self.add_transition(start, '_', ini_1);
+                let fin_1 = self.build(r1, ini_1);
+                let end = self.new_state();
+                self.add_transition(fin_1, '_', ini_1);
+                self.add_transition(fin_1, '_', end);
+                if let Regex::Star(_) = re {
+                    self.add_transition(start, '_', end);
+                }
+                end
+            }
+            Regex::Complement(_) | Regex::Intersection(_, _) => {
+                let m = regex_to_machine_over(re, &self.alphabet);
+                self.embed(&m, start)
+            }
+        }
+    }
+}
+
+fn thompson_over(re: &Regex, alphabet: &[char]) -> Machine {
+    let mut builder = ThompsonBuilder{ machine: Machine::new('T'), alphabet: alphabet.to_vec() };
+    let start = builder.new_state();
+    let end = builder.build(re, start);
+    builder.machine.lookup_state_mut(start).is_initial = true;
+    builder.machine.lookup_state_mut(end).is_final = true;
+    builder.machine
+}
+
+pub fn thompson(re: &Regex) -> Machine {
+    thompson_over(re, &re.alphabet())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::berry_sethi::*;
+    use crate::test_util::*;
+    use super::*;
+
+    const REGEXES: [&str; 6] = ["a", "_", "(a|b)*abb", "a+b*|c", "(ab|_)(c|d)+", "((a|b)*c)*"];
+
+    #[test]
+    fn equivalent_to_berry_sethi() {
+        for re in REGEXES.map(regex) {
+            assert_equivalent(&thompson(&re), &Machine::from_machine(berry_sethi(&re)));
+        }
+    }
+
+    #[test]
+    fn single_initial_and_final_state() {
+        for re in REGEXES.map(regex) {
+            let m = thompson(&re);
+            assert_eq!(m.initial_states_ids().len(), 1);
+            assert_eq!(m.final_states_ids().len(), 1);
+        }
+    }
+}
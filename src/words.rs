@@ -0,0 +1,84 @@
+use std::collections::*;
+
+use crate::fsm::*;
+
+impl Machine {
+    // Length of the shortest word leading from each state to a final one
+    fn distances_to_final(&self) -> HashMap<i32, usize> {
+        let mut res: HashMap<i32, usize> = HashMap::new();
+        let mut worklist: VecDeque<(i32, usize)> = self.final_states_ids().into_iter().map(|sid| (sid, 0)).collect();
+        while let Some((sid, dist)) = worklist.pop_front() {
+            if res.contains_key(&sid) {
+                continue;
+            }
+            res.insert(sid, dist);
+            worklist.extend(self.transitions_to(sid).iter().map(|(src_id, _)| (*src_id, dist + 1)));
+        }
+        res
+    }
+
+    // Lists the accepted words in length-lexicographic order
+    pub fn words(&self, max_len: usize, limit: usize) -> Vec<String> {
+        let dfa = self.determinized();
+        let useful = dfa.coreachable_states_ids();
+        let dist = dfa.distances_to_final();
+        let alphabet = dfa.alphabet();
+
+        let mut res: Vec<String> = vec![];
+        let mut frontier: Vec<(String, i32)> = dfa.initial_states_ids().into_iter().filter(|sid| {
+            useful.contains(sid)
+        }).map(|sid| (String::new(), sid)).collect();
+        for len in 0..=max_len {
+            for (word, sid) in &frontier {
+                if res.len() >= limit {
+                    return res;
+                }
+                if dfa.lookup_state(*sid).is_final {
+                    res.push(word.clone());
+                }
+            }
+            if len == max_len {
+                break;
+            }
+            frontier = frontier.iter().flat_map(|(word, sid)| {
+                alphabet.iter().filter_map(|c| {
+                    dfa.next_state(*sid, *c).map(|next| (format!("{word}{c}"), next))
+                }).filter(|(_, next)| useful.contains(next) && len + 1 + dist[next] <= max_len).collect::<Vec<_>>()
+            }).collect();
+            // Each entry leads to an accepted word of length len + 1 + dist, so
+            // the words still missing all extend the entries with the smallest
+            // such completions
+            frontier.sort_by_key(|(_, sid)| dist[sid]);
+            frontier.truncate(limit - res.len());
+            frontier.sort();
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::*;
+
+    #[test]
+    fn length_lexicographic_order() {
+        let fsm = regex_machine("(b|a)*c");
+        assert_eq!(fsm.words(10, 5), vec!["c", "ac", "bc", "aac", "abc"]);
+        assert_eq!(fsm.words(1, 10), vec!["c"]);
+    }
+
+    #[test]
+    fn limit_keeps_shortest_words() {
+        // The prefix "a" comes first but only leads to a much longer word
+        let fsm = regex_machine("aaaaaaaa|bc");
+        assert_eq!(fsm.words(10, 1), vec!["bc"]);
+        assert_eq!(fsm.words(10, 2), vec!["bc", "aaaaaaaa"]);
+        assert_eq!(fsm.words(5, 2), vec!["bc"]);
+    }
+
+    #[test]
+    fn empty_language_and_empty_word() {
+        assert!(load_machine("tests/fsm_min_empty_lang.txt").words(10, 10).is_empty());
+        assert_eq!(regex_machine("_|a").words(10, 10), vec!["", "a"]);
+    }
+}